@@ -1,42 +1,100 @@
-// None of these work very well, but they're fun to look at
+// None of these work very well, but they're fun to look at. Pick one with rating_system and compare it with fit.
 
-pub fn gen_rank_pure_elo_map(matches: &Vec<Match>, teams: &mut Vec<Team>, ranking_context: &RankingContext) {
-    const STARTING_RATING: f64 = 1500.0;
-    
-    for team in teams.iter_mut() {
-        team.elo = STARTING_RATING;
-    }
+use crate::data_loader::*;
+use crate::error::*;
+use crate::ranking::*;
+use crate::ranking_context::RankingContext;
+use crate::rating_system::*;
+
+/// What every team starts at when there's no seeding
+pub const STARTING_RATING: f64 = 1500.0;
+
+/// Plain Elo map by map, with everyone starting at STARTING_RATING
+pub fn gen_rank_pure_elo_map(matches: &[Match], events: &[Event], teams: &mut [Team], ranking_context: &RankingContext) -> Result<()> {
+    run_rating_system(&mut PureEloMap, matches, events, teams, ranking_context)
+}
+
+/// Plain Elo on whole matches, with everyone starting at STARTING_RATING. A 2-0 counts the same as a 2-1
+pub fn gen_rank_pure_elo(matches: &[Match], events: &[Event], teams: &mut [Team], ranking_context: &RankingContext) -> Result<()> {
+    run_rating_system(&mut PureElo, matches, events, teams, ranking_context)
+}
 
-    map_elo_adjustments(matches, teams);
+/// Seeded like gen_rank_new, but every map moves the Elo once for each round the winner won it by
+pub fn gen_rank_org_rounds(matches: &[Match], events: &[Event], teams: &mut [Team], ranking_context: &RankingContext) -> Result<()> {
+    run_rating_system(&mut OrgRounds, matches, events, teams, ranking_context)
 }
 
-pub fn gen_rank_pure_elo(matches: &Vec<Match>, teams: &mut Vec<Team>, ranking_context: &RankingContext) {
-    const STARTING_RATING: f64 = 1500.0;
-    
+fn start_everyone_equal(teams: &mut [Team]) {
     for team in teams.iter_mut() {
+        team.seed_points = STARTING_RATING;
         team.elo = STARTING_RATING;
     }
+}
+
+pub struct PureEloMap;
+
+impl RatingSystem for PureEloMap {
+    fn kind(&self) -> RatingSystemKind {
+        RatingSystemKind::PureEloMap
+    }
+
+    fn seed(&mut self, _matches: &[Match], _events: &[Event], teams: &mut [Team], _ranking_context: &RankingContext) -> Result<()> {
+        start_everyone_equal(teams);
+        Ok(())
+    }
+
+    fn update_map(&mut self, m: &Match, map: &Map, teams: &mut [Team], ranking_context: &RankingContext) {
+        apply_map_elo(m, map, teams, ranking_context, 1.0);
+    }
 
-    elo_adjustments(matches, teams);
+    fn predict(&self, team: &Team, opponent: &Team, ranking_context: &RankingContext) -> f64 {
+        win_probability(team.elo, opponent.elo, ranking_context)
+    }
 }
 
-pub fn gen_rank_org_rounds(matches: &Vec<Match>, events: &Vec<Event>, teams: &mut Vec<Team>, ranking_context: &RankingContext) {
-    seed_teams(matches, events, teams, ranking_context);
-    round_elo_adjustments(matches, teams);
+pub struct PureElo;
+
+impl RatingSystem for PureElo {
+    fn kind(&self) -> RatingSystemKind {
+        RatingSystemKind::PureElo
+    }
+
+    fn seed(&mut self, _matches: &[Match], _events: &[Event], teams: &mut [Team], _ranking_context: &RankingContext) -> Result<()> {
+        start_everyone_equal(teams);
+        Ok(())
+    }
+
+    fn update_match(&mut self, m: &Match, teams: &mut [Team], ranking_context: &RankingContext) {
+        let elo_diff = elo_result(teams[m.winner.0].elo, teams[m.loser().0].elo, ranking_context);
+
+        teams[m.winner.0].elo += elo_diff;
+        teams[m.loser().0].elo -= elo_diff;
+    }
+
+    fn predict(&self, team: &Team, opponent: &Team, ranking_context: &RankingContext) -> f64 {
+        win_probability(team.elo, opponent.elo, ranking_context)
+    }
 }
 
-fn round_elo_adjustments(matches: &Vec<Match>, teams: &mut Vec<Team>) {
-    for m in matches {
-        for map in &m.maps {
-            let round_delta = map.team_1_score.abs_diff(map.team_2_score) as f64;
+pub struct OrgRounds;
+
+impl RatingSystem for OrgRounds {
+    fn kind(&self) -> RatingSystemKind {
+        RatingSystemKind::OrgRounds
+    }
 
-            let map_winning_team = if map.team_1_score > map.team_2_score { m.team_1_id } else { m.team_2_id };
-            let map_losing_team  = m.team_1_id + m.team_2_id - map_winning_team;
+    fn seed(&mut self, matches: &[Match], events: &[Event], teams: &mut [Team], ranking_context: &RankingContext) -> Result<()> {
+        seed_teams(matches, events, teams, ranking_context)?;
+        Ok(())
+    }
 
-            let elo_diff = elo_result(teams[map_winning_team].elo, teams[map_losing_team].elo) * round_delta;
+    // A 13-2 moves eleven times as much as a 13-11, and a tied map doesn't move anything
+    fn update_map(&mut self, m: &Match, map: &Map, teams: &mut [Team], ranking_context: &RankingContext) {
+        let round_delta = map.team_1_score.abs_diff(map.team_2_score) as f64;
+        apply_map_elo(m, map, teams, ranking_context, round_delta);
+    }
 
-            teams[map_winning_team].elo += elo_diff;
-            teams[map_losing_team].elo -= elo_diff;
-        }
-    }        
-}
\ No newline at end of file
+    fn predict(&self, team: &Team, opponent: &Team, ranking_context: &RankingContext) -> f64 {
+        win_probability(team.elo, opponent.elo, ranking_context)
+    }
+}
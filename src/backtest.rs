@@ -0,0 +1,199 @@
+use serde::*;
+use std::fmt;
+use std::io::{self, Write};
+use crate::core_matching::CoreMatcher;
+use crate::data_loader::*;
+use crate::error::*;
+use crate::evaluation::*;
+use crate::ranking_context::RankingContext;
+use crate::rating_system::rank;
+use crate::util::format_date;
+
+const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+
+/// A walk-forward backtest. Unlike analyze_fit, which scores the ratings on the matches they were built from, every
+/// fold ranks the window before a cutoff and predicts the matches after it.
+#[derive(Serialize, Debug, Clone)]
+pub struct Backtest {
+    pub window_days: u32,
+    pub horizon_days: u32,
+    pub ranking_context: RankingContext,
+    pub folds: Vec<BacktestFold>,
+    /// Cutoffs with too little data before them to rank, or a window the context refuses, and why
+    pub skipped: Vec<(u32, String)>,
+    /// Every fold's predictions scored together. A match after more than one cutoff counts once for each
+    pub overall: Scores,
+    pub overall_maps: Scores,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BacktestFold {
+    pub cutoff: u32,
+    pub training_matches: usize,
+    pub test_matches: usize,
+    /// Test matches with a lineup that doesn't belong to any ranked team, so there's nothing to predict from
+    pub unmatched: usize,
+    pub scores: Scores,
+    /// The same predictions, once for every map of the match
+    pub map_scores: Scores,
+    #[serde(skip)]
+    pub predictions: Vec<Prediction>,
+    #[serde(skip)]
+    pub map_predictions: Vec<Prediction>,
+}
+
+/// Ranks the window_days before each cutoff, and predicts the matches in the horizon_days after it from the ratings.
+/// Lineups after the cutoff are matched to the ranked teams with the core matching rule, against the lineups each team
+/// played with before the cutoff. Everything but the time windows comes from ranking_context.
+pub fn backtest(dataset: &Dataset, ranking_context: &RankingContext, cutoffs: &[u32], window_days: u32, horizon_days: u32, buckets: usize) -> Result<Backtest> {
+    let mut folds = Vec::new();
+    let mut skipped = Vec::new();
+
+    for &cutoff in cutoffs {
+        let mut training_context = ranking_context.clone();
+        training_context.time_window_end = cutoff;
+        training_context.time_window_start = cutoff.saturating_sub(window_days.saturating_mul(SECONDS_PER_DAY));
+
+        // Early in the data there might not be enough teams to scale the factors by, or the window might be cut off
+        // at the beginning of time and end up shorter than the grace period
+        let trained = dataset.window(&training_context).and_then(|(matches, events, mut teams)| {
+            let system = rank(&matches, &events, &mut teams, &training_context)?;
+            Ok((matches, teams, system))
+        });
+
+        // Everything after the cutoff, without the grace period, which only matters for ranking
+        let mut test_context = ranking_context.clone();
+        test_context.time_window_start = cutoff.saturating_add(1);
+        test_context.time_window_end = cutoff.saturating_add(horizon_days.max(1).saturating_mul(SECONDS_PER_DAY));
+        test_context.time_grace_period = 0;
+        let tested = trained.and_then(|trained| Ok((trained, dataset.window(&test_context)?.0)));
+
+        let ((matches, teams, system), test_matches) = match tested {
+            Ok(fold) => fold,
+            Err(e @ (Error::Numeric(_) | Error::Context(_))) => { skipped.push((cutoff, e.to_string())); continue; }
+            Err(e) => return Err(e),
+        };
+
+        let matcher = trained_matcher(&teams, ranking_context);
+        let mut predictions = Vec::new();
+        let mut map_predictions = Vec::new();
+        for m in &test_matches {
+            let team_1 = matcher.find_team(&teams, &m.team_1_players);
+            let team_2 = matcher.find_team(&teams, &m.team_2_players);
+
+            // The same team on both sides can happen when two lineups share a core
+            let (Some((team_1, _)), Some((team_2, _))) = (team_1, team_2) else { continue };
+            if team_1 == team_2 { continue; }
+
+            let probability = system.predict(&teams[team_1.0], &teams[team_2.0], ranking_context);
+            predictions.push(Prediction { match_start_time: m.match_start_time, probability, outcome: m.winning_team == 1 });
+
+            for map in m.maps.iter().filter(|map| map.team_1_score != map.team_2_score) {
+                map_predictions.push(Prediction {
+                    match_start_time: m.match_start_time,
+                    probability,
+                    outcome: map.team_1_score > map.team_2_score,
+                });
+            }
+        }
+
+        folds.push(BacktestFold {
+            cutoff,
+            training_matches: matches.len(),
+            test_matches: test_matches.len(),
+            unmatched: test_matches.len() - predictions.len(),
+            scores: score(&predictions, buckets),
+            map_scores: score(&map_predictions, buckets),
+            predictions,
+            map_predictions,
+        });
+    }
+
+    let all: Vec<Prediction> = folds.iter().flat_map(|f| f.predictions.iter().copied()).collect();
+    let all_maps: Vec<Prediction> = folds.iter().flat_map(|f| f.map_predictions.iter().copied()).collect();
+
+    Ok(Backtest {
+        window_days,
+        horizon_days,
+        ranking_context: ranking_context.clone(),
+        overall: score(&all, buckets),
+        overall_maps: score(&all_maps, buckets),
+        folds,
+        skipped,
+    })
+}
+
+// A core matcher that has seen every lineup the ranked teams played with, oldest first, the way it would if they
+// played again after the cutoff
+fn trained_matcher(teams: &[Team], ranking_context: &RankingContext) -> Box<dyn CoreMatcher> {
+    let mut matcher = ranking_context.core_matching.matcher();
+    for t in teams {
+        for lineup in t.roster.lineups() {
+            matcher.observe(t.id, &lineup.players);
+        }
+    }
+    matcher
+}
+
+impl Backtest {
+    pub fn write_json<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writeln!(writer)?;
+        writer.flush()
+    }
+
+    /// One row per fold
+    pub fn write_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut csv_writer = csv::Writer::from_writer(writer);
+        csv_writer.write_record([
+            "cutoff_date", "cutoff", "training_matches", "test_matches", "unmatched", "predictions",
+            "log_loss", "brier", "accuracy", "auc", "calibration_error",
+            "maps", "map_log_loss", "map_brier", "map_accuracy", "map_auc", "map_calibration_error",
+        ])?;
+
+        for f in &self.folds {
+            csv_writer.write_record([
+                format_date(f.cutoff),
+                f.cutoff.to_string(),
+                f.training_matches.to_string(),
+                f.test_matches.to_string(),
+                f.unmatched.to_string(),
+                f.scores.predictions.to_string(),
+                f.scores.log_loss.to_string(),
+                f.scores.brier.to_string(),
+                f.scores.accuracy.to_string(),
+                f.scores.auc.map_or(String::new(), |auc| auc.to_string()),
+                f.scores.calibration_error.to_string(),
+                f.map_scores.predictions.to_string(),
+                f.map_scores.log_loss.to_string(),
+                f.map_scores.brier.to_string(),
+                f.map_scores.accuracy.to_string(),
+                f.map_scores.auc.map_or(String::new(), |auc| auc.to_string()),
+                f.map_scores.calibration_error.to_string(),
+            ])?;
+        }
+
+        csv_writer.flush()
+    }
+}
+
+impl fmt::Display for Backtest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Cutoff     | Trained on | Tested on | Predicted | Log loss | Brier  | Accuracy | AUC    | Calibration error")?;
+        for fold in &self.folds {
+            let auc = fold.scores.auc.map_or("-".to_string(), |auc| format!("{auc:.4}"));
+            writeln!(f, "{0} | {1:10} | {2:9} | {3:9} | {4:8.4} | {5:.4} | {6:8.4} | {7:6} | {8:.4}",
+                format_date(fold.cutoff), fold.training_matches, fold.test_matches, fold.scores.predictions,
+                fold.scores.log_loss, fold.scores.brier, fold.scores.accuracy, auc, fold.scores.calibration_error)?;
+        }
+        for (cutoff, reason) in &self.skipped {
+            writeln!(f, "{} | skipped: {reason}", format_date(*cutoff))?;
+        }
+        writeln!(f)?;
+
+        writeln!(f, "Every fold, per match:")?;
+        write!(f, "{}", self.overall)?;
+        writeln!(f, "Every fold, per map:")?;
+        write!(f, "{}", self.overall_maps)
+    }
+}
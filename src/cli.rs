@@ -0,0 +1,595 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use alternative_vrs::*;
+use alternative_vrs::util::{parse_date, parse_end_date};
+
+const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+const DEFAULT_WINDOW_DAYS: u32 = 6 * 30;
+const DEFAULT_CORE_LAST_N: usize = 5;
+const DEFAULT_REGION_MIN_PLAYERS: usize = 3;
+const DEFAULT_GLICKO_TAU: f64 = 0.5;
+const DEFAULT_GLICKO_VOLATILITY: f64 = 0.06;
+const DEFAULT_GLICKO_MAX_DEVIATION: f64 = 350.0;
+const DEFAULT_GLICKO_MIN_DEVIATION: f64 = 50.0;
+const DEFAULT_GLICKO: (f64, f64, f64, f64) = (DEFAULT_GLICKO_TAU, DEFAULT_GLICKO_VOLATILITY, DEFAULT_GLICKO_MAX_DEVIATION, DEFAULT_GLICKO_MIN_DEVIATION);
+
+#[derive(Parser, Debug)]
+#[command(version, about = "Alternative version of Valve's Regional Standings")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Ranks every team and prints the standings
+    Rank {
+        #[command(flatten)]
+        data: DataArgs,
+        #[command(flatten)]
+        model: ModelArgs,
+
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Write the standings to this file instead of the console. Only for --format json, csv, markdown and html
+        #[arg(long)]
+        output: Option<String>,
+    },
+
+    /// Ranks every team and reports how well the final ratings fit the matches they were built from
+    Fit {
+        #[command(flatten)]
+        data: DataArgs,
+        #[command(flatten)]
+        model: ModelArgs,
+
+        /// Also report the error for a handful of individual teams
+        #[arg(long)]
+        teams: bool,
+
+        /// Calibration buckets between 0 and 1 for the per match and per map scores
+        #[arg(long, default_value_t = 10)]
+        buckets: usize,
+    },
+
+    /// Reruns the model for combinations of settings, and lists the error of each, best first
+    Sweep {
+        #[command(flatten)]
+        data: DataArgs,
+        #[command(flatten)]
+        model: ModelArgs,
+
+        /// A ranking context field and its range, as field=from:to:step, e.g. elo_k=16:64:8. Repeat it to sweep
+        /// several fields at once
+        #[arg(long = "param", required = true)]
+        params: Vec<ParameterRange>,
+
+        /// Draw this many random combinations from the ranges instead of trying all of them
+        #[arg(long)]
+        samples: Option<usize>,
+        /// Seed for --samples. The same seed draws the same combinations
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Threads to run combinations on [default: one per CPU]
+        #[arg(long)]
+        threads: Option<usize>,
+
+        #[arg(long, value_enum, default_value_t = TableFormat::Table)]
+        format: TableFormat,
+
+        /// Write to this file instead of the console
+        #[arg(long)]
+        output: Option<String>,
+    },
+
+    /// Reports every match and event in the data that gets dropped or looks suspicious
+    Validate {
+        #[command(flatten)]
+        data: DataArgs,
+        #[command(flatten)]
+        model: ModelArgs,
+
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+    },
+
+    /// Lists which lineups the core matching rule merged into an existing team, and which it split off into a new one
+    Cores {
+        #[command(flatten)]
+        data: DataArgs,
+        #[command(flatten)]
+        model: ModelArgs,
+
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+    },
+
+    /// Explains a single team's rating: the events, opponents and maps behind it, and how they add up. Alternative VRS only
+    Explain {
+        #[command(flatten)]
+        data: DataArgs,
+        #[command(flatten)]
+        model: ModelArgs,
+
+        /// Team name (not case sensitive) or team ID
+        #[arg(long)]
+        team: String,
+
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+    },
+
+    /// Writes every map's rating change as CSV: both ratings before the map, the win probability and the delta.
+    /// Alternative VRS only
+    Ledger {
+        #[command(flatten)]
+        data: DataArgs,
+        #[command(flatten)]
+        model: ModelArgs,
+
+        /// Only the maps of this team, by name or ID
+        #[arg(long)]
+        team: Option<String>,
+
+        /// Write to this file instead of the console
+        #[arg(long)]
+        output: Option<String>,
+    },
+
+    /// Ranks the data again at a series of cutoff dates, and prints every ranked team at each cutoff
+    History {
+        /// Path to the HLTV match data JSON file
+        #[arg(long, default_value = "../data/matchdata_sample_20230829.json")]
+        data: String,
+        #[command(flatten)]
+        model: ModelArgs,
+
+        /// First cutoff, as YYYY-MM-DD or unix timestamp [default: --window-days before --to]
+        #[arg(long, value_parser = parse_date_arg)]
+        from: Option<u32>,
+        /// Last cutoff, as YYYY-MM-DD or unix timestamp. Defaults to the last match in the data
+        #[arg(long, value_parser = parse_date_arg)]
+        to: Option<u32>,
+        /// Days between cutoffs
+        #[arg(long, default_value_t = 7)]
+        step_days: u32,
+        /// Move the first cutoff to this weekday, e.g. monday
+        #[arg(long, value_parser = parse_weekday)]
+        weekday: Option<Weekday>,
+        /// Length of the time window before each cutoff, in days
+        #[arg(long, default_value_t = DEFAULT_WINDOW_DAYS)]
+        window_days: u32,
+
+        #[arg(long, value_enum, default_value_t = HistoryFormat::Csv)]
+        format: HistoryFormat,
+        /// Write to this file instead of the console
+        #[arg(long)]
+        output: Option<String>,
+    },
+
+    /// Walk-forward backtest: ranks the window before each cutoff, and scores how well the ratings predict the matches after it
+    Backtest {
+        /// Path to the HLTV match data JSON file
+        #[arg(long, default_value = "../data/matchdata_sample_20230829.json")]
+        data: String,
+        #[command(flatten)]
+        model: ModelArgs,
+
+        /// First cutoff, as YYYY-MM-DD or unix timestamp [default: --window-days before --to]
+        #[arg(long, value_parser = parse_date_arg)]
+        from: Option<u32>,
+        /// Last cutoff, as YYYY-MM-DD or unix timestamp. Defaults to --horizon-days before the last match in the data
+        #[arg(long, value_parser = parse_date_arg)]
+        to: Option<u32>,
+        /// Days between cutoffs
+        #[arg(long, default_value_t = 30)]
+        step_days: u32,
+        /// Length of the time window before each cutoff, in days
+        #[arg(long, default_value_t = DEFAULT_WINDOW_DAYS)]
+        window_days: u32,
+        /// Days after each cutoff to predict
+        #[arg(long, default_value_t = 30)]
+        horizon_days: u32,
+        /// Calibration buckets between 0 and 1
+        #[arg(long, default_value_t = 10)]
+        buckets: usize,
+
+        #[arg(long, value_enum, default_value_t = TableFormat::Table)]
+        format: TableFormat,
+        /// Write to this file instead of the console
+        #[arg(long)]
+        output: Option<String>,
+    },
+
+    /// Ranks two or more models on the same matches and compares their fit, scores and standings. Without --against
+    /// or --system, that's the default model and the configured one
+    Compare {
+        #[command(flatten)]
+        data: DataArgs,
+        #[command(flatten)]
+        model: ModelArgs,
+
+        /// Another model to compare with the configured one, as a TOML or JSON config file. Repeatable. The model
+        /// flags don't apply to it, and it's ranked in the first model's time window
+        #[arg(long)]
+        against: Vec<String>,
+        /// The configured model with another rating system. Repeatable
+        #[arg(long = "system", value_enum)]
+        systems: Vec<RatingSystemArg>,
+        /// Flag teams whose rank moves by more than this many places from the first model
+        #[arg(long, default_value_t = 5)]
+        max_move: usize,
+        /// Calibration buckets between 0 and 1
+        #[arg(long, default_value_t = 10)]
+        buckets: usize,
+
+        #[arg(long, value_enum, default_value_t = TableFormat::Table)]
+        format: TableFormat,
+        /// Write to this file instead of the console
+        #[arg(long)]
+        output: Option<String>,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    /// Rank, name, Elo and core players
+    Table,
+    /// Every factor, seed points and Elo
+    Detailed,
+    /// Standings per region, with the regional and global rank
+    Regional,
+    /// Every team with every factor, plus the ranking context and data window
+    Json,
+    /// Same as json, one row per team, with the metadata in a .meta.json next to it. Needs --output
+    Csv,
+    /// Sorted table with every factor, under a header with the window and model settings
+    Markdown,
+    /// Same as markdown, as an HTML page
+    Html,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum CoreRule {
+    /// Compare with the team's most recent lineup, which never changes
+    FirstSeen,
+    /// Compare with the lineup the team last played with, going back in time
+    Rolling,
+    /// Weigh each player by how many of the team's last --core-last-n lineups they played in
+    Weighted,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum RegionRuleArg {
+    /// More than half of the core
+    Majority,
+    /// Whichever region most of the core is from, unless it's a tie
+    Plurality,
+    /// At least --region-min-players of the core
+    AtLeast,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum RatingSystemArg {
+    /// Seeded from the four factors, then Elo map by map
+    AlternativeVrs,
+    /// Glicko-2 from the same seeds, map by map, with a rating deviation for every team
+    Glicko2,
+    /// Elo map by map from 1500, without seeding
+    PureEloMap,
+    /// Elo on whole matches from 1500, without seeding
+    PureElo,
+    /// Seeded Elo where each map counts once for every round it was won by
+    OrgRounds,
+    /// Valve's original VRS, for comparison
+    OriginalVrs,
+}
+
+impl RatingSystemArg {
+    /// The value it's given as on the command line, e.g. glicko2
+    pub fn name(self) -> String {
+        self.to_possible_value().expect("every rating system can be picked").get_name().to_string()
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum HistoryFormat {
+    Csv,
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum TableFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum ReportFormat {
+    Text,
+    Json,
+}
+
+#[derive(Args, Debug)]
+pub struct DataArgs {
+    /// Path to the HLTV match data JSON file
+    #[arg(long, default_value = "../data/matchdata_sample_20230829.json")]
+    pub data: String,
+
+    /// End of the time window, as YYYY-MM-DD (the end of that day, UTC) or unix timestamp. Defaults to the last match in the data
+    #[arg(long, value_parser = parse_end_date_arg)]
+    pub end: Option<u32>,
+
+    /// Start of the time window, as YYYY-MM-DD or unix timestamp. Overrides --window-days
+    #[arg(long, value_parser = parse_date_arg)]
+    pub start: Option<u32>,
+
+    /// Length of the time window in days, counting back from --end [default: 180]
+    #[arg(long)]
+    pub window_days: Option<u32>,
+}
+
+// Every field of RankingContext except the time window, which is set through DataArgs.
+// Anything left out keeps the value from --config, or the default value if there's no config file.
+#[derive(Args, Debug)]
+pub struct ModelArgs {
+    /// TOML or JSON file with RankingContext fields. Flags override the file
+    #[arg(long)]
+    pub config: Option<String>,
+
+    #[arg(long)]
+    pub top_outlier_count: Option<usize>,
+    #[arg(long)]
+    pub factor_bucket_size: Option<usize>,
+
+    #[arg(long)]
+    pub elo_k: Option<f64>,
+    #[arg(long)]
+    pub elo_delta: Option<f64>,
+
+    /// In seconds
+    #[arg(long)]
+    pub time_grace_period: Option<u32>,
+    #[arg(long)]
+    pub time_decay_factor: Option<f64>,
+
+    #[arg(long)]
+    pub max_prize_pool_mod: Option<f64>,
+
+    #[arg(long)]
+    pub opponent_winnings_weight: Option<f64>,
+    #[arg(long)]
+    pub prize_money_weight: Option<f64>,
+    #[arg(long)]
+    pub opponent_network_weight: Option<f64>,
+    #[arg(long)]
+    pub event_participation_weight: Option<f64>,
+
+    #[arg(long)]
+    pub min_seeded_rank: Option<f64>,
+    #[arg(long)]
+    pub max_seeded_rank: Option<f64>,
+
+    #[arg(long)]
+    pub min_matches_for_ranking: Option<u32>,
+    #[arg(long)]
+    pub min_wins_for_ranking: Option<u32>,
+    /// Rank teams by their rating deviation instead of --min-matches-for-ranking, if the rating system keeps one
+    #[arg(long)]
+    pub max_deviation_for_ranking: Option<f64>,
+
+    /// How lineups are grouped into teams [default: first-seen]
+    #[arg(long, value_enum)]
+    pub core_matching: Option<CoreRule>,
+    /// Players a lineup needs in common with a team to be that team [default: 3]. Only the weighted rule takes a
+    /// fraction
+    #[arg(long)]
+    pub core_min_overlap: Option<f64>,
+    /// Lineups the weighted rule looks back over [default: 5]
+    #[arg(long)]
+    pub core_last_n: Option<usize>,
+
+    /// How teams are assigned to regions from their core's nationalities [default: majority]
+    #[arg(long, value_enum)]
+    pub region_rule: Option<RegionRuleArg>,
+    /// Players the at-least rule needs from a region, 1 to 5 [default: 3]
+    #[arg(long)]
+    pub region_min_players: Option<usize>,
+
+    /// What rates the teams [default: alternative-vrs]
+    #[arg(long, value_enum)]
+    pub rating_system: Option<RatingSystemArg>,
+    /// How fast Glicko-2 volatility can change [default: 0.5]
+    #[arg(long)]
+    pub glicko_tau: Option<f64>,
+    /// Glicko-2 volatility every team starts with [default: 0.06]
+    #[arg(long)]
+    pub glicko_volatility: Option<f64>,
+    /// Glicko-2 deviation of a team without any matches [default: 350]
+    #[arg(long)]
+    pub glicko_max_deviation: Option<f64>,
+    /// Lowest deviation Glicko-2 lets a team get to, however much it played [default: 50]
+    #[arg(long)]
+    pub glicko_min_deviation: Option<f64>,
+}
+
+impl DataArgs {
+    // Sets the time window on the ranking context. Flags win over whatever the config file said, and if neither
+    // sets the end of the window, we have to peek at the data to find the last match.
+    pub fn apply(&self, ranking_context: &mut RankingContext, dataset: &Dataset) -> Result<()> {
+        let default_context = RankingContext::default();
+
+        let end = match self.end {
+            Some(end) => end,
+            None if ranking_context.time_window_end != default_context.time_window_end => ranking_context.time_window_end,
+            None => dataset.latest_match_time()?,
+        };
+
+        let start = match (self.start, self.window_days) {
+            (Some(start), _) => start,
+            (None, Some(days)) => end.saturating_sub(days.saturating_mul(SECONDS_PER_DAY)),
+            (None, None) if ranking_context.time_window_start != default_context.time_window_start => ranking_context.time_window_start,
+            (None, None) => end.saturating_sub(DEFAULT_WINDOW_DAYS * SECONDS_PER_DAY),
+        };
+
+        ranking_context.time_window_end = end;
+        ranking_context.time_window_start = start;
+        Ok(())
+    }
+}
+
+impl ModelArgs {
+    pub fn apply(&self, ranking_context: &mut RankingContext) -> std::result::Result<(), ContextError> {
+        fn set<T: Copy>(field: &mut T, value: Option<T>) {
+            if let Some(v) = value { *field = v; }
+        }
+
+        set(&mut ranking_context.top_outlier_count, self.top_outlier_count);
+        set(&mut ranking_context.factor_bucket_size, self.factor_bucket_size);
+
+        set(&mut ranking_context.elo_k, self.elo_k);
+        set(&mut ranking_context.elo_delta, self.elo_delta);
+
+        set(&mut ranking_context.time_grace_period, self.time_grace_period);
+        set(&mut ranking_context.time_decay_factor, self.time_decay_factor);
+
+        set(&mut ranking_context.max_prize_pool_mod, self.max_prize_pool_mod);
+
+        set(&mut ranking_context.opponet_winnings_weight, self.opponent_winnings_weight);
+        set(&mut ranking_context.prize_money_weight, self.prize_money_weight);
+        set(&mut ranking_context.opponent_network_weight, self.opponent_network_weight);
+        set(&mut ranking_context.event_participation_weight, self.event_participation_weight);
+
+        set(&mut ranking_context.min_seeded_rank, self.min_seeded_rank);
+        set(&mut ranking_context.max_seeded_rank, self.max_seeded_rank);
+
+        set(&mut ranking_context.min_matches_for_ranking, self.min_matches_for_ranking);
+        set(&mut ranking_context.min_wins_for_ranking, self.min_wins_for_ranking);
+        if self.max_deviation_for_ranking.is_some() {
+            ranking_context.max_deviation_for_ranking = self.max_deviation_for_ranking;
+        }
+
+        // The rule and its settings are one field, so anything the flags don't set comes from the current rule
+        let (rule, min_overlap, last_n) = match ranking_context.core_matching {
+            CoreMatching::FirstSeen { min_overlap } => (CoreRule::FirstSeen, min_overlap as f64, DEFAULT_CORE_LAST_N),
+            CoreMatching::Rolling { min_overlap } => (CoreRule::Rolling, min_overlap as f64, DEFAULT_CORE_LAST_N),
+            CoreMatching::Weighted { last_n, min_overlap } => (CoreRule::Weighted, min_overlap, last_n),
+        };
+        let rule = self.core_matching.unwrap_or(rule);
+        let min_overlap = self.core_min_overlap.unwrap_or(min_overlap);
+        let last_n = self.core_last_n.unwrap_or(last_n);
+
+        // Only the weighted rule counts fractions of a player, the others need a whole number rather than a guess
+        if rule != CoreRule::Weighted && (min_overlap.fract() != 0.0 || min_overlap < 0.0) {
+            return Err(ContextError::InvalidFieldValue { field: "core_min_overlap".to_string(), value: min_overlap });
+        }
+
+        ranking_context.core_matching = match rule {
+            CoreRule::FirstSeen => CoreMatching::FirstSeen { min_overlap: min_overlap as usize },
+            CoreRule::Rolling => CoreMatching::Rolling { min_overlap: min_overlap as usize },
+            CoreRule::Weighted => CoreMatching::Weighted { last_n, min_overlap },
+        };
+
+        let (rule, min_players) = match ranking_context.region_rule {
+            RegionRule::Majority => (RegionRuleArg::Majority, DEFAULT_REGION_MIN_PLAYERS),
+            RegionRule::Plurality => (RegionRuleArg::Plurality, DEFAULT_REGION_MIN_PLAYERS),
+            RegionRule::AtLeast { players } => (RegionRuleArg::AtLeast, players),
+        };
+        let min_players = self.region_min_players.unwrap_or(min_players);
+
+        ranking_context.region_rule = match self.region_rule.unwrap_or(rule) {
+            RegionRuleArg::Majority => RegionRule::Majority,
+            RegionRuleArg::Plurality => RegionRule::Plurality,
+            RegionRuleArg::AtLeast => RegionRule::AtLeast { players: min_players },
+        };
+
+        let system = match ranking_context.rating_system {
+            RatingSystemKind::AlternativeVrs => RatingSystemArg::AlternativeVrs,
+            RatingSystemKind::Glicko2 { .. } => RatingSystemArg::Glicko2,
+            RatingSystemKind::PureEloMap => RatingSystemArg::PureEloMap,
+            RatingSystemKind::PureElo => RatingSystemArg::PureElo,
+            RatingSystemKind::OrgRounds => RatingSystemArg::OrgRounds,
+            RatingSystemKind::OriginalVrs => RatingSystemArg::OriginalVrs,
+        };
+        ranking_context.rating_system = self.rating_system_kind(self.rating_system.unwrap_or(system), ranking_context);
+        Ok(())
+    }
+
+    // The rating system and its settings are one field, so any Glicko-2 setting the flags don't set comes from the
+    // current system, if that's Glicko-2 too
+    pub fn rating_system_kind(&self, system: RatingSystemArg, ranking_context: &RankingContext) -> RatingSystemKind {
+        let (tau, volatility, max_deviation, min_deviation) = match ranking_context.rating_system {
+            RatingSystemKind::Glicko2 { tau, volatility, max_deviation, min_deviation } => (tau, volatility, max_deviation, min_deviation),
+            _ => DEFAULT_GLICKO,
+        };
+
+        match system {
+            RatingSystemArg::AlternativeVrs => RatingSystemKind::AlternativeVrs,
+            RatingSystemArg::Glicko2 => RatingSystemKind::Glicko2 {
+                tau: self.glicko_tau.unwrap_or(tau),
+                volatility: self.glicko_volatility.unwrap_or(volatility),
+                max_deviation: self.glicko_max_deviation.unwrap_or(max_deviation),
+                min_deviation: self.glicko_min_deviation.unwrap_or(min_deviation),
+            },
+            RatingSystemArg::PureEloMap => RatingSystemKind::PureEloMap,
+            RatingSystemArg::PureElo => RatingSystemKind::PureElo,
+            RatingSystemArg::OrgRounds => RatingSystemKind::OrgRounds,
+            RatingSystemArg::OriginalVrs => RatingSystemKind::OriginalVrs,
+        }
+    }
+}
+
+// Builds the ranking context from the command line, starting from the config file or the defaults
+pub fn build_context(data: &DataArgs, model: &ModelArgs, dataset: &Dataset) -> Result<RankingContext> {
+    let mut ranking_context = model_context(model)?;
+    data.apply(&mut ranking_context, dataset)?;
+
+    ranking_context.validate()?;
+    Ok(ranking_context)
+}
+
+// Same as build_context, for the commands that set a time window of their own for every cutoff
+pub fn model_context(model: &ModelArgs) -> Result<RankingContext> {
+    let mut ranking_context = match &model.config {
+        Some(path) => RankingContext::from_file(path)?,
+        None => RankingContext::default(),
+    };
+
+    model.apply(&mut ranking_context)?;
+    ranking_context.validate()?;
+    Ok(ranking_context)
+}
+
+// Looks a team up by ID or by name. Several cores can share a name, so that's an error rather than a guess
+pub fn find_team(teams: &[Team], query: &str) -> std::result::Result<TeamId, String> {
+    if let Ok(id) = query.parse::<usize>() {
+        if let Some(t) = teams.iter().find(|t| t.id == TeamId(id)) { return Ok(t.id); }
+    }
+
+    let found: Vec<&Team> = teams.iter().filter(|t| t.name.eq_ignore_ascii_case(query)).collect();
+    match found[..] {
+        [t] => Ok(t.id),
+        [] => Err(format!("there's no team called {query}")),
+        _ => Err(format!("several teams are called {query}, pick one by ID: {}",
+            found.iter().map(|t| t.id.to_string()).collect::<Vec<_>>().join(", "))),
+    }
+}
+
+fn parse_weekday(input: &str) -> std::result::Result<Weekday, String> {
+    let weekdays = [
+        ("monday", Weekday::Monday), ("tuesday", Weekday::Tuesday), ("wednesday", Weekday::Wednesday), ("thursday", Weekday::Thursday),
+        ("friday", Weekday::Friday), ("saturday", Weekday::Saturday), ("sunday", Weekday::Sunday),
+    ];
+
+    weekdays.iter().find(|(name, _)| name.eq_ignore_ascii_case(input)).map(|&(_, weekday)| weekday)
+        .ok_or_else(|| format!("'{input}' isn't a weekday"))
+}
+
+fn parse_date_arg(input: &str) -> std::result::Result<u32, String> {
+    parse_date(input).ok_or_else(|| format!("'{input}' is neither a YYYY-MM-DD date nor a unix timestamp"))
+}
+
+fn parse_end_date_arg(input: &str) -> std::result::Result<u32, String> {
+    parse_end_date(input).ok_or_else(|| format!("'{input}' is neither a YYYY-MM-DD date nor a unix timestamp"))
+}
@@ -0,0 +1,201 @@
+use serde::*;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use crate::data_loader::*;
+use crate::error::*;
+use crate::evaluation::*;
+use crate::ranking_context::RankingContext;
+use crate::rating_system::rank;
+use crate::report::ranked_teams;
+use crate::fit::{fit_by_bucket, Fit};
+
+/// Several models ranked on the same matches, side by side. The first model is the one the others are compared with.
+#[derive(Serialize, Debug, Clone)]
+pub struct Comparison {
+    pub models: Vec<ComparedModel>,
+    /// Every team that's ranked in at least one model, in the order of the first model
+    pub teams: Vec<TeamComparison>,
+    /// Teams that move more than this many places from their rank in the first model are flagged
+    pub max_move: usize,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ComparedModel {
+    pub name: String,
+    pub ranking_context: RankingContext,
+    /// The same buckets and error as analyze_fit
+    pub fit: Fit,
+    /// Per match and per map, see evaluate
+    pub evaluation: Evaluation,
+}
+
+/// A team is the same team in two models if it has the same name and core. Models that group lineups differently
+/// can end up with a team that only shows up in some of them.
+#[derive(Serialize, Debug, Clone)]
+pub struct TeamComparison {
+    pub name: String,
+    pub core: Vec<String>,
+    /// In the same order as the models. None if the team isn't ranking eligible in that model
+    pub ranks: Vec<Option<usize>>,
+    pub elos: Vec<Option<f64>>,
+    /// The most places the team moves from its rank in the first model. None unless it's ranked in the first model
+    /// and at least one other
+    pub largest_move: Option<usize>,
+    pub flagged: bool,
+}
+
+/// Ranks the dataset with every model, each with its own settings but all in the first model's time window, so they're
+/// scored on the same matches, and compares the results. Calibration uses bucket_count buckets, and teams that move
+/// more than max_move places are flagged.
+pub fn compare_models(dataset: &Dataset, models: &[(String, RankingContext)], bucket_count: usize, max_move: usize) -> Result<Comparison> {
+    let mut compared = Vec::new();
+    let mut teams: Vec<TeamComparison> = Vec::new();
+    let mut team_index: HashMap<(String, Vec<u16>), usize> = HashMap::new();
+
+    for (idx, (name, model)) in models.iter().enumerate() {
+        let mut ranking_context = model.clone();
+        ranking_context.time_window_start = models[0].1.time_window_start;
+        ranking_context.time_window_end = models[0].1.time_window_end;
+        ranking_context.validate()?;
+
+        let (matches, events, mut ranked) = dataset.window(&ranking_context)?;
+        let system = rank(&matches, &events, &mut ranked, &ranking_context)?;
+
+        compared.push(ComparedModel {
+            name: name.clone(),
+            fit: fit_by_bucket(&ranked, &matches, &ranking_context)?,
+            evaluation: evaluate(&ranked, &matches, &ranking_context, system.as_ref(), bucket_count)?,
+            ranking_context,
+        });
+        let ranking_context = &compared[idx].ranking_context;
+
+        for (rank, t) in ranked_teams(&ranked, ranking_context) {
+            let key = (t.name.clone(), t.core.iter().map(|p| p.player_id).collect());
+            let row = *team_index.entry(key).or_insert_with(|| {
+                teams.push(TeamComparison {
+                    name: t.name.clone(),
+                    core: t.core.iter().map(|p| p.nick.clone()).collect(),
+                    ranks: vec![None; models.len()],
+                    elos: vec![None; models.len()],
+                    largest_move: None,
+                    flagged: false,
+                });
+                teams.len() - 1
+            });
+
+            teams[row].ranks[idx] = Some(rank);
+            teams[row].elos[idx] = Some(t.elo);
+        }
+    }
+
+    for t in teams.iter_mut() {
+        let Some(first) = t.ranks[0] else { continue };
+        t.largest_move = t.ranks[1..].iter().flatten().map(|rank| rank.abs_diff(first)).max();
+        t.flagged = t.largest_move.is_some_and(|moved| moved > max_move);
+    }
+
+    // Ranked in the first model first, then by the best rank anywhere else
+    teams.sort_by_key(|t| (t.ranks[0].is_none(), t.ranks[0], t.ranks.iter().flatten().min().copied(), t.name.clone()));
+
+    Ok(Comparison { models: compared, teams, max_move })
+}
+
+impl Comparison {
+    pub fn flagged(&self) -> impl Iterator<Item = &TeamComparison> {
+        self.teams.iter().filter(|t| t.flagged)
+    }
+
+    pub fn write_json<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writeln!(writer)?;
+        writer.flush()
+    }
+
+    /// One row per team, with a rank and Elo column for every model. The models' scores are in the JSON
+    pub fn write_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut csv_writer = csv::Writer::from_writer(writer);
+
+        let mut header = vec!["name".to_string(), "core".to_string()];
+        for m in &self.models {
+            header.extend([format!("rank_{}", m.name), format!("elo_{}", m.name)]);
+        }
+        header.extend(["largest_move".to_string(), "flagged".to_string()]);
+        csv_writer.write_record(&header)?;
+
+        for t in &self.teams {
+            let mut record = vec![t.name.clone(), t.core.join(" ")];
+            for (rank, elo) in t.ranks.iter().zip(&t.elos) {
+                record.extend([
+                    rank.map(|r| r.to_string()).unwrap_or_default(),
+                    elo.map(|e| e.to_string()).unwrap_or_default(),
+                ]);
+            }
+            record.extend([t.largest_move.map(|m| m.to_string()).unwrap_or_default(), t.flagged.to_string()]);
+            csv_writer.write_record(&record)?;
+        }
+
+        csv_writer.flush()
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.models.iter().map(|m| m.name.len()).max().unwrap_or(0).max(20);
+        let systems: Vec<String> = self.models.iter().map(|m| m.ranking_context.rating_system.to_string()).collect();
+        let system_width = systems.iter().map(|s| s.len()).max().unwrap_or(0).max(13);
+
+        writeln!(f, "{0:width$} | {1:system_width$} | Fit error | Log loss | Brier  | Accuracy | AUC", "Model", "Rating system")?;
+        for (m, system) in self.models.iter().zip(&systems) {
+            let scores = &m.evaluation.matches;
+            let auc = scores.auc.map_or("-".to_string(), |auc| format!("{auc:.4}"));
+            writeln!(f, "{0:width$} | {1:system_width$} | {2:9.4} | {3:8.4} | {4:.4} | {5:8.4} | {6}",
+                m.name, system, m.fit.error, scores.log_loss, scores.brier, scores.accuracy, auc)?;
+        }
+        writeln!(f)?;
+
+        // analyze_fit's buckets, one column per model
+        writeln!(f, "Underdog win rate, actual / expected, and matches played:")?;
+        write!(f, "EWR Bucket")?;
+        for m in &self.models {
+            write!(f, " | {0:width$}", m.name)?;
+        }
+        writeln!(f)?;
+        let buckets = self.models.iter().map(|m| m.fit.buckets.len()).max().unwrap_or(0);
+        for i in 0..buckets {
+            let from = self.models.iter().find_map(|m| m.fit.buckets.get(i)).map_or(0.0, |b| b.from);
+            write!(f, "{from:10.2}")?;
+            for m in &self.models {
+                let cell = m.fit.buckets.get(i).map_or("-".to_string(), |b| {
+                    format!("{:4.2} / {:4.2} ({})", b.win_rate(), b.expected_win_rate(), b.played)
+                });
+                write!(f, " | {cell:width$}")?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f)?;
+
+        write!(f, "{0:25}", "Team")?;
+        for m in &self.models {
+            write!(f, " | {0:>width$}", m.name)?;
+        }
+        writeln!(f, " | Moved")?;
+        for t in &self.teams {
+            write!(f, "{0:25}", t.name)?;
+            for (idx, rank) in t.ranks.iter().enumerate() {
+                let cell = match (rank, t.ranks[0]) {
+                    (None, _) => "-".to_string(),
+                    (Some(rank), Some(first)) if idx > 0 && *rank != first => format!("{rank} ({:+})", first as i64 - *rank as i64),
+                    (Some(rank), _) => rank.to_string(),
+                };
+                write!(f, " | {cell:>width$}")?;
+            }
+            let moved = t.largest_move.map_or("-".to_string(), |m| m.to_string());
+            writeln!(f, " | {moved}{}", if t.flagged { " *" } else { "" })?;
+        }
+
+        let flagged = self.flagged().count();
+        writeln!(f)?;
+        writeln!(f, "{flagged} teams moved more than {} places, marked with *", self.max_move)
+    }
+}
@@ -0,0 +1,258 @@
+use serde::*;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use crate::data_loader::*;
+use crate::registry::*;
+
+/// Decides whether a lineup is a team we've already seen, or a new one.
+/// load_data walks the matches newest first, and calls observe every time it assigns a lineup to a team.
+pub trait CoreMatcher {
+    fn rule(&self) -> CoreMatching;
+
+    /// The team this lineup belongs to, with how much it overlaps. None if it's a new team.
+    fn find_team(&self, teams: &[Team], lineup: &[Player]) -> Option<(TeamId, f64)>;
+
+    fn observe(&mut self, _team: TeamId, _lineup: &[Player]) {}
+}
+
+/// The built-in core matching rules. This is what goes in the RankingContext, and it's turned into a CoreMatcher
+/// when loading the data.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum CoreMatching {
+    /// The VRS rule: a lineup is the same team if it shares min_overlap players with the first lineup we saw for it.
+    /// Since we walk newest first, that's the team's most recent lineup, and it's never updated.
+    FirstSeen { min_overlap: usize },
+
+    /// Like FirstSeen, but compared with the last lineup we assigned to the team. A team that replaces one player
+    /// at a time stays the same team.
+    Rolling { min_overlap: usize },
+
+    /// Each player counts for the share of the team's last last_n lineups they played in, so a lineup is the same
+    /// team if those shares add up to min_overlap.
+    Weighted { last_n: usize, min_overlap: f64 },
+}
+
+impl Default for CoreMatching {
+    fn default() -> Self {
+        CoreMatching::FirstSeen { min_overlap: 3 }
+    }
+}
+
+impl CoreMatching {
+    pub fn matcher(&self) -> Box<dyn CoreMatcher> {
+        match *self {
+            CoreMatching::FirstSeen { min_overlap } => Box::new(FirstSeenCore { min_overlap }),
+            CoreMatching::Rolling { min_overlap } => Box::new(RollingCore { min_overlap, latest: Vec::new() }),
+            CoreMatching::Weighted { last_n, min_overlap } => Box::new(WeightedCore { last_n, min_overlap, recent: Vec::new() }),
+        }
+    }
+}
+
+impl fmt::Display for CoreMatching {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CoreMatching::FirstSeen { min_overlap } => write!(f, "first seen core, {min_overlap} players"),
+            CoreMatching::Rolling { min_overlap } => write!(f, "rolling core, {min_overlap} players"),
+            CoreMatching::Weighted { last_n, min_overlap } => write!(f, "weighted over the last {last_n} lineups, {min_overlap} players"),
+        }
+    }
+}
+
+pub struct FirstSeenCore {
+    pub min_overlap: usize,
+}
+
+impl CoreMatcher for FirstSeenCore {
+    fn rule(&self) -> CoreMatching {
+        CoreMatching::FirstSeen { min_overlap: self.min_overlap }
+    }
+
+    // First team that's similar enough wins, even if a later one is more similar
+    fn find_team(&self, teams: &[Team], lineup: &[Player]) -> Option<(TeamId, f64)> {
+        teams.iter()
+            .map(|t| (t.id, overlap(&t.core, lineup)))
+            .find(|(_, similarity)| *similarity >= self.min_overlap)
+            .map(|(id, similarity)| (id, similarity as f64))
+    }
+}
+
+pub struct RollingCore {
+    pub min_overlap: usize,
+    // Last lineup assigned to each team, indexed by TeamId
+    latest: Vec<Vec<Player>>,
+}
+
+impl CoreMatcher for RollingCore {
+    fn rule(&self) -> CoreMatching {
+        CoreMatching::Rolling { min_overlap: self.min_overlap }
+    }
+
+    fn find_team(&self, _teams: &[Team], lineup: &[Player]) -> Option<(TeamId, f64)> {
+        let scores = self.latest.iter().enumerate()
+            .map(|(idx, latest)| (TeamId(idx), overlap(latest, lineup) as f64));
+
+        best_match(scores, self.min_overlap as f64)
+    }
+
+    fn observe(&mut self, team: TeamId, lineup: &[Player]) {
+        if self.latest.len() <= team.0 {
+            self.latest.resize(team.0 + 1, Vec::new());
+        }
+        self.latest[team.0] = lineup.to_vec();
+    }
+}
+
+pub struct WeightedCore {
+    pub last_n: usize,
+    pub min_overlap: f64,
+    // The last last_n lineups assigned to each team, indexed by TeamId
+    recent: Vec<VecDeque<Vec<Player>>>,
+}
+
+impl CoreMatcher for WeightedCore {
+    fn rule(&self) -> CoreMatching {
+        CoreMatching::Weighted { last_n: self.last_n, min_overlap: self.min_overlap }
+    }
+
+    fn find_team(&self, _teams: &[Team], lineup: &[Player]) -> Option<(TeamId, f64)> {
+        let scores = self.recent.iter().enumerate().map(|(idx, recent)| {
+            let mut score = 0.0;
+            for p in lineup {
+                let appearances = recent.iter().filter(|l| l.iter().any(|r| r.player_id == p.player_id)).count();
+                score += appearances as f64 / recent.len().max(1) as f64;
+            }
+            (TeamId(idx), score)
+        });
+
+        best_match(scores, self.min_overlap)
+    }
+
+    fn observe(&mut self, team: TeamId, lineup: &[Player]) {
+        if self.recent.len() <= team.0 {
+            self.recent.resize(team.0 + 1, VecDeque::new());
+        }
+
+        let recent = &mut self.recent[team.0];
+        recent.push_back(lineup.to_vec());
+        while recent.len() > self.last_n {
+            recent.pop_front();
+        }
+    }
+}
+
+// Players the two lineups have in common
+fn overlap(a: &[Player], b: &[Player]) -> usize {
+    a.iter().filter(|p1| b.iter().any(|p2| p1.player_id == p2.player_id)).count()
+}
+
+// Highest score above the threshold. Ties go to the older team
+fn best_match(scores: impl Iterator<Item = (TeamId, f64)>, min_overlap: f64) -> Option<(TeamId, f64)> {
+    let mut best: Option<(TeamId, f64)> = None;
+    for (id, score) in scores {
+        if score < min_overlap { continue; }
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((id, score));
+        }
+    }
+    best
+}
+
+/// What insert_team did with a lineup
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoreAssignment {
+    Existing { team: TeamId, overlap: f64 },
+    New(TeamId),
+}
+
+impl CoreAssignment {
+    pub fn team(&self) -> TeamId {
+        match *self {
+            CoreAssignment::Existing { team, .. } => team,
+            CoreAssignment::New(team) => team,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum CoreDecisionKind {
+    /// A lineup that isn't identical to what it was compared with joined an existing team
+    Merged { overlap: f64 },
+    /// A lineup that didn't match any team, under an HLTV team ID that already belongs to another team
+    Split { previous: TeamId },
+    /// A lineup that didn't match any team, under an HLTV team ID we haven't seen yet
+    New,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct CoreDecision {
+    pub match_start_time: u32,
+    pub hltv_team_id: usize,
+    pub team_name: String,
+    pub lineup: Vec<String>,
+    pub team: TeamId,
+    #[serde(flatten)]
+    pub kind: CoreDecisionKind,
+}
+
+/// Every merge and split the core matching rule made while loading the data
+#[derive(Serialize, Debug, Clone)]
+pub struct CoreLog {
+    pub rule: CoreMatching,
+    pub decisions: Vec<CoreDecision>,
+    #[serde(skip)]
+    last_team: HashMap<usize, TeamId>,
+}
+
+impl CoreLog {
+    pub fn new(rule: CoreMatching) -> Self {
+        Self { rule, decisions: Vec::new(), last_team: HashMap::new() }
+    }
+
+    pub fn record(&mut self, m: &Match, team_slot: usize, assignment: CoreAssignment) {
+        let (hltv_team_id, team_name, lineup) = match team_slot {
+            1 => (m.team_1_id, &m.team_1_name, &m.team_1_players),
+            _ => (m.team_2_id, &m.team_2_name, &m.team_2_players),
+        };
+
+        let previous = self.last_team.insert(hltv_team_id, assignment.team());
+
+        let kind = match assignment {
+            // Identical lineups aren't interesting
+            CoreAssignment::Existing { overlap, .. } if overlap >= lineup.len() as f64 => return,
+            CoreAssignment::Existing { overlap, .. } => CoreDecisionKind::Merged { overlap },
+            CoreAssignment::New(_) => match previous {
+                Some(previous) => CoreDecisionKind::Split { previous },
+                None => CoreDecisionKind::New,
+            },
+        };
+
+        self.decisions.push(CoreDecision {
+            match_start_time: m.match_start_time,
+            hltv_team_id,
+            team_name: team_name.clone(),
+            lineup: lineup.iter().map(|p| p.nick.clone()).collect(),
+            team: assignment.team(),
+            kind,
+        });
+    }
+}
+
+impl fmt::Display for CoreLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Core matching: {}", self.rule)?;
+
+        for d in &self.decisions {
+            let outcome = match &d.kind {
+                CoreDecisionKind::Merged { overlap } => format!("merged into team {} (overlap {overlap:.2})", d.team),
+                CoreDecisionKind::Split { previous } => format!("split from team {previous} into new team {}", d.team),
+                CoreDecisionKind::New => format!("new team {}", d.team),
+            };
+
+            writeln!(f, "{0:10} | {1:20} | {2:50} | {3}", d.match_start_time, d.team_name, d.lineup.join(", "), outcome)?;
+        }
+
+        Ok(())
+    }
+}
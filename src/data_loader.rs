@@ -1,122 +1,249 @@
 #![allow(dead_code)]
 
 use serde::*;
+use std::collections::HashMap;
 use std::fs;
 use serde_aux::field_attributes::deserialize_number_from_string;
 use crate::ranking_context::RankingContext;
+use crate::core_matching::*;
+use crate::regions::*;
+use crate::roster::RosterHistory;
+use crate::error::*;
+use crate::registry::*;
+use crate::validation::{validate_match_data, ValidationReport};
+
+/// Loads data from JSON file specified with file path.
+/// Every match and placement keeps its HLTV IDs, and gets a TeamId and EventId on top. Those refer to the index in the
+/// team and event lists, which the ranking uses to look them up. See registry.rs to go between the two.
+/// Match vector is sorted chronologically, oldest first. Matches that start at the same time keep their order from the file.
+/// map_elo_adjustments doesn't rely on this, but it's the order everything else expects.
+/// Lineups are grouped into teams by the rule in ranking_context.core_matching.
+/// To rank the same file more than once, load a Dataset and window it instead.
+pub fn load_data(file_path: String, ranking_context: &RankingContext) -> Result<(Vec<Match>, Vec<Event>, Vec<Team>)> {
+    Dataset::load(&file_path)?.window(ranking_context)
+}
+
+/// What load_data returns, plus the log of which lineups the core matching rule merged and split
+pub type LoadedData = (Vec<Match>, Vec<Event>, Vec<Team>, CoreLog);
+
+/// Same as load_data, but with any core matching rule
+pub fn load_data_with(file_path: String, ranking_context: &RankingContext, matcher: &mut dyn CoreMatcher) -> Result<LoadedData> {
+    Dataset::load(&file_path)?.window_with(ranking_context, matcher)
+}
 
-// Loads data from JSON file specified with file path.
-// Note that Team and Event IDs are converted to refer to their index in both lists. Thus, neither list should ever be sorted
-// Match vector is sorted chronologically, which is important for the Elo calculation later on, but in theory, nothing should break
-// if it gets sorted some other way. 
-pub fn load_data(file_path: String, ranking_context: &RankingContext) -> (Vec<Match>, Vec<Event>, Vec<Team>) {
-    let mut events: Vec<Event> = Vec::new();
-    let mut matches: Vec<Match> = Vec::new();
-    let mut teams: Vec<Team> = Vec::new();
-
-    // Returns struct MatchData with all matches and events.
-    let data = fs::read_to_string(file_path).expect("Invalid path!");
-    let match_data: MatchData = serde_json::from_str(&data[..]).expect("Failed, man");
-
-    // Add events 
-    for i in match_data.events {
-        if events.iter().any(|ev| ev.id == i.id) { continue; }
-        events.push(Event::new(i));
+/// The match data file, parsed once and never changed. Reading and parsing the file is by far the slowest part of
+/// loading, so sweeps, backtests and histories load a Dataset once and window it for every ranking context they try.
+#[derive(Debug)]
+pub struct Dataset {
+    pub file_path: String,
+    match_data: MatchData,
+}
+
+impl Dataset {
+    pub fn load(file_path: &str) -> Result<Self> {
+        Ok(Self {
+            file_path: file_path.to_string(),
+            match_data: read_match_data(file_path)?,
+        })
     }
 
-    // Add matches
-    'matches: for i in match_data.matches {
-        // Removes if not played in our time frame, or if there were fewer than five players.
-        if     i.team_1_players.len() != 5
-            || i.team_2_players.len() != 5
-            || i.match_start_time < ranking_context.time_window_start
-            || i.match_start_time > ranking_context.time_window_end   {continue; }
+    /// Matches in the file, including the ones a window would drop
+    pub fn matches_read(&self) -> usize {
+        self.match_data.matches.len()
+    }
 
-        let mut m = i.clone();
+    /// Finds the start time of the last match in the file. Used to default the end of the time window
+    pub fn latest_match_time(&self) -> Result<u32> {
+        self.match_data.matches.iter().map(|m| m.match_start_time).max()
+            .ok_or_else(|| Error::Numeric(format!("{} doesn't have any matches", self.file_path)))
+    }
+
+    /// Reports every record window would drop, refuse or should be suspicious of. See validation.rs
+    pub fn validate(&self, ranking_context: &RankingContext) -> ValidationReport {
+        validate_match_data(&self.match_data, ranking_context)
+    }
 
-        'events: for (ev_index, ev) in events.iter_mut().enumerate() {
-            if ev.id != m.event_id { continue 'events; }
+    /// The matches, events and teams in ranking_context's time window, the same as load_data would return them
+    pub fn window(&self, ranking_context: &RankingContext) -> Result<(Vec<Match>, Vec<Event>, Vec<Team>)> {
+        let mut matcher = ranking_context.core_matching.matcher();
+        let (matches, events, teams, _) = self.window_with(ranking_context, matcher.as_mut())?;
 
-            // Remove Showmatches (obviously imperfect)
-            if ev.name.to_lowercase().contains("showmatch") { continue 'matches; }
+        Ok((matches, events, teams))
+    }
 
-            // Updates match's Event reference to be index on the event list
-            m.event_id = ev_index;
+    /// Same as window, but with any core matching rule
+    pub fn window_with(&self, ranking_context: &RankingContext, matcher: &mut dyn CoreMatcher) -> Result<LoadedData> {
+        ranking_context.validate()?;
 
-            // Finds the last match at the event
-            ev.last_match_time = u32::max(ev.last_match_time, m.match_start_time);
+        let mut events: Vec<Event> = Vec::new();
+        let mut matches: Vec<Match> = Vec::new();
+        let mut teams: Vec<Team> = Vec::new();
+
+        // Decides which matches we skip, and refuses data we can't make sense of. See validation.rs
+        let report = self.validate(ranking_context);
+        if let Some(e) = report.first_error() { return Err(e); }
+        let dropped_matches = report.dropped_matches();
+
+        // Add events 
+        for i in &self.match_data.events {
+            if events.iter().any(|ev| ev.hltv_id == i.id) { continue; }
+            events.push(Event::new(EventId(events.len()), i.clone()));
         }
 
-        // Information context, i.e. factor that decreases for older matches
-        m.information_context = ranking_context.time_mod(m.match_start_time);    
+        // Add matches. Only the ones we keep are copied out of the data set
+        for (index, m) in self.match_data.matches.iter().enumerate() {
+            // Removes if not played in our time frame, if there were fewer than five players, showmatches and so on.
+            if dropped_matches.contains(&index) { continue; }
+            let mut m = m.clone();
 
-        matches.push(m);
-    }
-    
-    // Add "teams" the way VRS defines them, which is based on cores. We need to sort the match feed for the core system to function properly
-    matches.sort_by(|a, b| b.match_start_time.cmp(&a.match_start_time));
-
-    for m in &mut matches {
-        // Checks if each core is "new", in which case they get added to the list. Returns that cores index in either case.
-        let team_one_idx = insert_team(&mut teams, &m.team_1_name, &m.team_1_players);
-        let team_two_idx = insert_team(&mut teams, &m.team_2_name, &m.team_2_players);
-
-        // Update event team_id reference
-        for pd in &mut events[m.event_id].prize_distribution {
-            if m.team_1_id == pd.team_id {
-                pd.team_id = team_one_idx;
-                pd.is_in_ranking = true;
-            }
-            if m.team_2_id == pd.team_id {
-                pd.team_id = team_two_idx;
-                pd.is_in_ranking = false;
+            for ev in events.iter_mut() {
+                if ev.hltv_id != m.event_id { continue; }
+
+                m.event = ev.id;
+
+                // Finds the last match at the event
+                ev.last_match_time = u32::max(ev.last_match_time, m.match_start_time);
             }
+
+            // Information context, i.e. factor that decreases for older matches
+            m.information_context = ranking_context.time_mod(m.match_start_time);    
+
+            matches.push(m);
+        }
+        
+        matches.sort_by_key(|m| m.match_start_time);
+
+        // Add "teams" the way VRS defines them, which is based on cores. Cores are found newest first, so that each team
+        // is named and cored after its most recent lineup. This is the only pass that walks the matches backwards.
+        let mut event_cores = EventCores::new();
+        let mut core_log = CoreLog::new(matcher.rule());
+        for m in matches.iter_mut().rev() {
+            // Checks if each core is "new", in which case they get added to the list. Returns that cores ID in either case.
+            let team_1 = insert_team(&mut teams, &m.team_1_name, &m.team_1_players, matcher)?;
+            let team_2 = insert_team(&mut teams, &m.team_2_name, &m.team_2_players, matcher)?;
+            core_log.record(m, 1, team_1);
+            core_log.record(m, 2, team_2);
+
+            m.team_1 = team_1.team();
+            m.team_2 = team_2.team();
+            m.winner = if m.winning_team == 1 { m.team_1 } else { m.team_2 };
+
+            event_cores.record(m.event, m.team_1_id, m.team_1);
+            event_cores.record(m.event, m.team_2_id, m.team_2);
+
+            teams[m.team_1.0].add_hltv_id(m.team_1_id);
+            teams[m.team_2.0].add_hltv_id(m.team_2_id);
+
+            // Set matches played and matches won. These are used to filter out teams at the very end
+            teams[m.team_1.0].matches_played += 1;
+            teams[m.team_2.0].matches_played += 1;
+            teams[m.winner.0].matches_won += 1;
         }
 
-        // Set ID to index on match list
-        m.winning_team = if m.winning_team == 1 { team_one_idx } else { team_two_idx };
-        m.team_1_id = team_one_idx;
-        m.team_2_id = team_two_idx;
+        // Each team's lineups over time, stand-ins included
+        for m in &matches {
+            teams[m.team_1.0].roster.record(m.match_start_time, &m.team_1_players);
+            teams[m.team_2.0].roster.record(m.match_start_time, &m.team_2_players);
+        }
 
-        // Set matches played and matches won. These are used to filter out teams at the very end
-        teams[team_one_idx].matches_played += 1;
-        teams[team_two_idx].matches_played += 1;
-        teams[m.winning_team].matches_won += 1;
-    }
+        map_prize_distributions(&mut events, &event_cores);
+        assign_regions(&mut teams, ranking_context.region_rule);
 
+        Ok((matches, events, teams, core_log))
+    }
+}
 
-    (matches, events, teams)
+// Which cores played under each HLTV team ID at each event, and how many matches they played
+struct EventCores {
+    // (event, HLTV team ID) -> (core, matches played), in the order the cores were first seen
+    cores: HashMap<(EventId, usize), Vec<(TeamId, u32)>>,
 }
 
-// Checks if team has a core of another team. If not, adds to team list. Returns index in the team list
-pub fn insert_team(teams: &mut Vec<Team>, team_name: &str, team_players: &Vec<Player>) -> usize {
-    for (idx, t) in teams.iter().enumerate() {
-        let mut similarity = 0;
-        for p1 in &t.core {
-            for p2 in team_players {
-                if p1.player_id == p2.player_id { similarity += 1; }
-            }
+impl EventCores {
+    fn new() -> Self {
+        Self { cores: HashMap::new() }
+    }
 
-            // Same team for our purposes
-            if similarity >= 3 {
-                return idx;
+    fn record(&mut self, event: EventId, hltv_team_id: usize, core: TeamId) {
+        let cores = self.cores.entry((event, hltv_team_id)).or_default();
+
+        match cores.iter_mut().find(|(id, _)| *id == core) {
+            Some((_, played)) => *played += 1,
+            None => cores.push((core, 1)),
+        }
+    }
+
+    // The core that played the most matches for this team at this event. If a stand-in made it a tie, we go with
+    // the core we saw first, which is the most recent one.
+    fn core_at_event(&self, event: EventId, hltv_team_id: usize) -> Option<TeamId> {
+        let cores = self.cores.get(&(event, hltv_team_id))?;
+
+        let mut best: Option<(TeamId, u32)> = None;
+        for &(core, played) in cores {
+            if best.is_none_or(|(_, best_played)| played > best_played) {
+                best = Some((core, played));
             }
         }
-    };
 
-    teams.push(Team::new(team_name.to_owned(),[
+        best.map(|(core, _)| core)
+    }
+}
+
+// Points every prize placement at the core that actually played the event, which also marks it as counted.
+// Placements for teams that didn't play any matches in our window stay out of the ranking.
+fn map_prize_distributions(events: &mut [Event], event_cores: &EventCores) {
+    for ev in events.iter_mut() {
+        for pd in &mut ev.prize_distribution {
+            pd.team = event_cores.core_at_event(ev.id, pd.team_id);
+        }
+    }
+}
+
+/// Finds the start time of the last match in the file. Used to default the end of the time window
+pub fn latest_match_time(file_path: &str) -> Result<u32> {
+    Dataset::load(file_path)?.latest_match_time()
+}
+
+pub(crate) fn read_match_data(file_path: &str) -> Result<MatchData> {
+    let data = fs::read_to_string(file_path).map_err(|source| Error::Io { path: file_path.to_string(), source })?;
+
+    // serde_path_to_error keeps track of where in the file we were, so schema errors can point at the bad field
+    let deserializer = &mut serde_json::Deserializer::from_str(&data);
+    serde_path_to_error::deserialize(deserializer).map_err(|e| Error::Json {
+        path: file_path.to_string(),
+        field: e.path().to_string(),
+        source: e.into_inner(),
+    })
+}
+
+/// Checks if team has a core of another team, according to matcher. If not, adds to team list.
+/// Either way, the returned assignment has the ID of the team, i.e. its index in the list
+pub fn insert_team(teams: &mut Vec<Team>, team_name: &str, team_players: &[Player], matcher: &mut dyn CoreMatcher) -> Result<CoreAssignment> {
+    // Same team for our purposes
+    if let Some((team, overlap)) = matcher.find_team(teams, team_players) {
+        matcher.observe(team, team_players);
+        return Ok(CoreAssignment::Existing { team, overlap });
+    }
+
+    if team_players.len() < 5 {
+        return Err(Error::IncompleteLineup { team_name: team_name.to_owned(), players: team_players.len() });
+    }
+
+    let id = TeamId(teams.len());
+    teams.push(Team::new(id, team_name.to_owned(),[
         team_players[0].clone(),
         team_players[1].clone(),
         team_players[2].clone(),
         team_players[3].clone(),
         team_players[4].clone(),
     ]));
+    matcher.observe(id, team_players);
 
-    teams.len() - 1
+    Ok(CoreAssignment::New(id))
 }
 
 // No clue in retrospect why this is a separate struct, but it doesn't really matter
-#[derive(Serialize,Deserialize,Debug)]
+#[derive(Serialize,Deserialize,Debug,Clone)]
 pub struct JsonEvent {
     #[serde(deserialize_with = "deserialize_number_from_string")]
     #[serde(rename(deserialize = "eventId"))]
@@ -135,25 +262,32 @@ pub struct JsonEvent {
 // As an added bonus, we can handle incredibly particular tournament prize pools
 #[derive(Debug)]
 pub struct Event {
-    pub id: usize,
+    pub id: EventId,
+    pub hltv_id: usize,
     pub name: String,
     pub prize_pool: f64,
+    /// The prize pool HLTV lists, e.g. "$1,000,000", or 0 if it isn't a number. The original VRS went by this one
+    pub listed_prize_pool: f64,
     pub prize_distribution: Vec<PrizeDist>,
     pub is_lan: bool,
     pub last_match_time: u32,
 }
 
 impl Event {
-    pub fn new(json_event: JsonEvent) -> Self {
+    pub fn new(id: EventId, json_event: JsonEvent) -> Self {
         let mut prize_pool = 0.0;
         for prize_moneys in &json_event.prize_distribution {
             prize_pool += prize_moneys.prize;
         }
 
+        let listed_prize_pool = json_event.prize_pool.replace("$","").replace(",","").parse::<f64>().unwrap_or(0.0);
+
         Self {
-            id: json_event.id,
+            id,
+            hltv_id: json_event.id,
             name: json_event.name,
             prize_pool,
+            listed_prize_pool,
             prize_distribution: json_event.prize_distribution,
             is_lan: json_event.is_lan,
             last_match_time: 0,
@@ -162,11 +296,13 @@ impl Event {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-struct MatchData {
+pub(crate) struct MatchData {
     pub matches: Vec<Match>,
     pub events: Vec<JsonEvent>,
 }
 
+/// team_1_id, team_2_id, event_id and winning_team (1 or 2) are exactly as HLTV has them. load_data fills in
+/// our own IDs for the same things in team_1, team_2, event and winner.
 #[derive(Serialize,Deserialize,Debug,Clone)]
 pub struct Match {
     #[serde(rename(deserialize = "matchStartTime"))]
@@ -194,21 +330,30 @@ pub struct Match {
     pub winning_team: usize,
     #[serde(default = "default_information_context")] 
     pub information_context: f64,
+
+    #[serde(skip_deserializing)]
+    pub team_1: TeamId,
+    #[serde(skip_deserializing)]
+    pub team_2: TeamId,
+    #[serde(skip_deserializing)]
+    pub winner: TeamId,
+    #[serde(skip_deserializing)]
+    pub event: EventId,
 }
 
 impl Match {
-    pub fn losing_team_id(&self) -> usize {
-        self.team_1_id + self.team_2_id - self.winning_team
+    pub fn loser(&self) -> TeamId {
+        self.other_team(self.winner)
     }
 
-    pub fn is_in_game(&self, id: usize) -> bool {
-        self.team_1_id == id || self.team_2_id == id
+    pub fn is_in_game(&self, id: TeamId) -> bool {
+        self.team_1 == id || self.team_2 == id
     }
 
-    pub fn other_team(&self, id: usize) -> usize {
-        debug_assert!(id == self.team_1_id || id == self.team_2_id);
+    pub fn other_team(&self, id: TeamId) -> TeamId {
+        debug_assert!(self.is_in_game(id));
 
-        self.team_1_id + self.team_2_id - id
+        if id == self.team_1 { self.team_2 } else { self.team_1 }
     }
 }
 
@@ -244,22 +389,35 @@ pub struct Map {
     pub team_2_score: u16,
 }
 
-#[derive(Serialize,Deserialize,Debug)]
+/// team_id is the HLTV team ID. team is the core that played the event under it, if there was one in our window.
+#[derive(Serialize,Deserialize,Debug,Clone)]
 pub struct PrizeDist {
     pub placement: u32,
     #[serde(deserialize_with = "deserialize_number_from_string")]
     #[serde(rename(deserialize = "teamId"))]
     pub team_id: usize,
-    #[serde(default="default_false")]
-    pub is_in_ranking: bool,
+    #[serde(skip_deserializing)]
+    pub team: Option<TeamId>,
     pub prize: f64,
     pub shared: bool,
 }
 
+impl PrizeDist {
+    pub fn is_in_ranking(&self) -> bool {
+        self.team.is_some()
+    }
+}
+
+/// A team as VRS defines it: a core of players, named after whatever the team was called when we first saw them.
+/// core is what lineups are matched against, roster is who actually played. The four factors are filled in by gen_rank_new.
 #[derive(Debug, Serialize, Clone)]
 pub struct Team {
+    pub id: TeamId,
+    pub hltv_ids: Vec<usize>,
     pub name: String,
     pub core: [Player; 5],
+    pub roster: RosterHistory,
+    pub region: Option<Region>,
     pub event_participation: f64,
     pub opponent_network: f64,
     pub opponent_winnings: f64,
@@ -268,6 +426,8 @@ pub struct Team {
     pub sum_of_factors: f64,
     pub seed_points: f64,
     pub elo: f64,
+    /// How unsure the rating system is of elo, in the same points. None for systems that don't keep track
+    pub rating_deviation: Option<f64>,
 
     pub own_network: f64,
     pub adjusted_winnings: f64,
@@ -277,10 +437,14 @@ pub struct Team {
 }
 
 impl Team {
-    pub fn new(name: String, core: [Player; 5]) -> Self {
+    pub fn new(id: TeamId, name: String, core: [Player; 5]) -> Self {
         Self {
+            id,
+            hltv_ids: Vec::new(),
             name,
             core,
+            roster: RosterHistory::default(),
+            region: None,
             event_participation: 0.0,
             opponent_network: 0.0,
             opponent_winnings: 0.0,
@@ -289,6 +453,7 @@ impl Team {
             sum_of_factors: 0.0,
             seed_points: 0.0,
             elo: 0.0,
+            rating_deviation: None,
 
             own_network: 0.0,
             adjusted_winnings: 0.0,
@@ -297,8 +462,13 @@ impl Team {
             matches_won: 0,
         }
     }
+
+    pub fn add_hltv_id(&mut self, hltv_id: usize) {
+        if !self.hltv_ids.contains(&hltv_id) {
+            self.hltv_ids.push(hltv_id);
+        }
+    }
 }
 
 fn default_information_context() -> f64 { 1.0 }
-fn default_false() -> bool { false }
 fn empty_string() -> String { "".to_string() }
\ No newline at end of file
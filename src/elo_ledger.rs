@@ -0,0 +1,110 @@
+use serde::*;
+use std::io::{self, Write};
+use crate::data_loader::*;
+use crate::registry::*;
+use crate::util::format_date;
+
+/// Every rating change map_elo_adjustments_with_ledger made, in the order it made them
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct EloLedger {
+    pub entries: Vec<LedgerEntry>,
+}
+
+/// A single map. Everything is from team 1's point of view, like the match it came from.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct LedgerEntry {
+    pub match_start_time: u32,
+    pub event: EventId,
+    pub team_1: TeamId,
+    pub team_2: TeamId,
+    pub team_1_name: String,
+    pub team_2_name: String,
+
+    pub map_name: String,
+    pub team_1_score: u16,
+    pub team_2_score: u16,
+
+    pub team_1_elo_before: f64,
+    pub team_2_elo_before: f64,
+    /// The chance team 1 had of winning the map, going by the ratings before it
+    pub team_1_win_probability: f64,
+    /// What team 1 gained, and team 2 lost. Negative if team 2 won the map
+    pub delta: f64,
+}
+
+impl LedgerEntry {
+    pub fn new(m: &Match, map: &Map, team_1_elo_before: f64, team_2_elo_before: f64, team_1_win_probability: f64, delta: f64) -> Self {
+        Self {
+            match_start_time: m.match_start_time,
+            event: m.event,
+            team_1: m.team_1,
+            team_2: m.team_2,
+            team_1_name: m.team_1_name.clone(),
+            team_2_name: m.team_2_name.clone(),
+
+            map_name: map.map_name.clone(),
+            team_1_score: map.team_1_score,
+            team_2_score: map.team_2_score,
+
+            team_1_elo_before,
+            team_2_elo_before,
+            team_1_win_probability,
+            delta,
+        }
+    }
+
+    pub fn is_in_game(&self, team: TeamId) -> bool {
+        self.team_1 == team || self.team_2 == team
+    }
+
+    /// The team's rating before the map and the change, from its own point of view
+    pub fn for_team(&self, team: TeamId) -> Option<(f64, f64)> {
+        if team == self.team_1 { return Some((self.team_1_elo_before, self.delta)); }
+        if team == self.team_2 { return Some((self.team_2_elo_before, -self.delta)); }
+        None
+    }
+}
+
+impl EloLedger {
+    pub fn entries_for(&self, team: TeamId) -> impl Iterator<Item = &LedgerEntry> {
+        self.entries.iter().filter(move |e| e.is_in_game(team))
+    }
+
+    /// The team's rating after each of its maps, as (match start time, Elo). Good for charts.
+    pub fn rating_history(&self, team: TeamId) -> Vec<(u32, f64)> {
+        self.entries_for(team)
+            .filter_map(|e| e.for_team(team).map(|(before, change)| (e.match_start_time, before + change)))
+            .collect()
+    }
+
+    /// One row per map, with the date next to the unix timestamp
+    pub fn write_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut csv_writer = csv::Writer::from_writer(writer);
+        csv_writer.write_record([
+            "date", "match_start_time", "event", "team_1", "team_2", "team_1_name", "team_2_name",
+            "map_name", "team_1_score", "team_2_score",
+            "team_1_elo_before", "team_2_elo_before", "team_1_win_probability", "delta",
+        ])?;
+
+        for e in &self.entries {
+            csv_writer.write_record([
+                format_date(e.match_start_time),
+                e.match_start_time.to_string(),
+                e.event.to_string(),
+                e.team_1.to_string(),
+                e.team_2.to_string(),
+                e.team_1_name.clone(),
+                e.team_2_name.clone(),
+                e.map_name.clone(),
+                e.team_1_score.to_string(),
+                e.team_2_score.to_string(),
+                e.team_1_elo_before.to_string(),
+                e.team_2_elo_before.to_string(),
+                e.team_1_win_probability.to_string(),
+                e.delta.to_string(),
+            ])?;
+        }
+
+        csv_writer.flush()
+    }
+}
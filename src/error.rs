@@ -0,0 +1,82 @@
+use std::fmt;
+use crate::ranking_context::ContextError;
+use crate::rating_system::RatingSystemKind;
+use crate::registry::TeamId;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The match data file couldn't be read
+    Io { path: String, source: std::io::Error },
+
+    /// An export couldn't be written
+    Write { path: String, source: std::io::Error },
+
+    /// The match data doesn't fit the HLTV schema. `field` is the path to the offending value, e.g. `matches[12].team1Id`
+    Json { path: String, field: String, source: serde_json::Error },
+
+    /// A match refers to an event that isn't in the event list
+    UnknownEvent { event_id: usize, match_start_time: u32, team_1_name: String, team_2_name: String },
+
+    /// An event record can't be loaded for any other reason
+    InvalidEvent { event_id: usize, reason: String },
+
+    /// A lineup we tried to build a core from doesn't have five players
+    IncompleteLineup { team_name: String, players: usize },
+
+    /// The ranking was handed a team list with a team missing, e.g. after filtering it
+    MissingTeam(TeamId),
+
+    /// A calculation produced NaN, or there's too little data to calculate it at all
+    Numeric(String),
+
+    /// Something that only the Alternative VRS can do was asked of another rating system, e.g. explaining a rating
+    UnsupportedRatingSystem { what: &'static str, system: RatingSystemKind },
+
+    /// A report couldn't be turned into JSON
+    Serialize(serde_json::Error),
+
+    Context(ContextError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "could not read {path}: {source}"),
+            Error::Write { path, source } => write!(f, "could not write {path}: {source}"),
+            Error::Json { path, field, source } => write!(f, "invalid match data in {path} at {field}: {source}"),
+            Error::UnknownEvent { event_id, match_start_time, team_1_name, team_2_name } => write!(f,
+                "{team_1_name} vs {team_2_name} (started {match_start_time}) refers to event {event_id}, which isn't in the event list"
+            ),
+            Error::InvalidEvent { event_id, reason } => write!(f, "event {event_id} can't be loaded: {reason}"),
+            Error::IncompleteLineup { team_name, players } => write!(f, "{team_name} has {players} players, a core needs five"),
+            Error::MissingTeam(id) => write!(f, "team {id} is missing from the team list, the ranking needs every team load_data returned"),
+            Error::Numeric(message) => write!(f, "{message}"),
+            Error::UnsupportedRatingSystem { what, system } => write!(f,
+                "{what} only works with the Alternative VRS, and the rating system is {system}"
+            ),
+            Error::Serialize(source) => write!(f, "could not serialize to JSON: {source}"),
+            Error::Context(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Write { source, .. } => Some(source),
+            Error::Json { source, .. } => Some(source),
+            Error::Serialize(source) => Some(source),
+            Error::Context(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ContextError> for Error {
+    fn from(e: ContextError) -> Self {
+        Error::Context(e)
+    }
+}
@@ -0,0 +1,243 @@
+use serde::*;
+use std::fmt;
+use crate::data_loader::*;
+use crate::error::*;
+use crate::ranking_context::RankingContext;
+use crate::rating_system::RatingSystem;
+use crate::registry::TeamIndex;
+
+/// A forecast for one match or map, and how it went
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct Prediction {
+    pub match_start_time: u32,
+    /// The chance team 1 had of winning, going by the ratings
+    pub probability: f64,
+    /// Whether team 1 won
+    pub outcome: bool,
+}
+
+impl Prediction {
+    fn outcome_value(&self) -> f64 {
+        if self.outcome { 1.0 } else { 0.0 }
+    }
+}
+
+/// Predictions grouped by probability, for checking that e.g. 70% favourites win about 70% of the time. One point of
+/// a reliability diagram.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct CalibrationBucket {
+    pub from: f64,
+    pub to: f64,
+    pub predictions: usize,
+    pub wins: usize,
+    /// Average predicted probability
+    pub expected: f64,
+    /// How often team 1 actually won
+    pub observed: f64,
+    /// 95% Wilson interval around observed. If expected is outside it, the bucket is off by more than chance
+    pub observed_low: f64,
+    pub observed_high: f64,
+}
+
+/// How good a set of predictions was. Lower is better for log loss, Brier score and calibration error, higher for
+/// accuracy and AUC
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Scores {
+    pub predictions: usize,
+    pub log_loss: f64,
+    pub brier: f64,
+    /// Share of predictions where the favourite won. A coin flip counts as half right
+    pub accuracy: f64,
+    /// Chance that a random win was given a higher probability than a random loss. None without both
+    pub auc: Option<f64>,
+    /// Like analyze_fit: the difference between actual and expected wins in each bucket, summed and divided by the
+    /// number of predictions
+    pub calibration_error: f64,
+    pub buckets: Vec<CalibrationBucket>,
+}
+
+/// Scores for the same ratings per match and per map
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    pub matches: Scores,
+    pub maps: Scores,
+}
+
+// Keeps a confident miss from costing an infinite log loss
+const MIN_PROBABILITY: f64 = 1e-15;
+
+// 95% confidence
+const WILSON_Z: f64 = 1.96;
+
+/// Scores predictions with bucket_count calibration buckets between 0 and 1. All zero if there aren't any predictions.
+pub fn score(predictions: &[Prediction], bucket_count: usize) -> Scores {
+    let bucket_count = bucket_count.max(1);
+    let n = predictions.len() as f64;
+
+    let mut log_loss = 0.0;
+    let mut brier = 0.0;
+    let mut correct = 0.0;
+    let mut played = vec![0usize; bucket_count];
+    let mut wins = vec![0usize; bucket_count];
+    let mut expected = vec![0.0; bucket_count];
+
+    for p in predictions {
+        let outcome = p.outcome_value();
+        let probability = p.probability.clamp(MIN_PROBABILITY, 1.0 - MIN_PROBABILITY);
+
+        log_loss -= outcome * probability.ln() + (1.0 - outcome) * (1.0 - probability).ln();
+        brier += (p.probability - outcome).powi(2);
+
+        if p.probability == 0.5 {
+            correct += 0.5;
+        } else if (p.probability > 0.5) == p.outcome {
+            correct += 1.0;
+        }
+
+        // A certain win would land in a bucket of its own
+        let bucket = ((p.probability * bucket_count as f64).floor() as usize).min(bucket_count - 1);
+        played[bucket] += 1;
+        expected[bucket] += p.probability;
+        if p.outcome { wins[bucket] += 1; }
+    }
+
+    let calibration_error: f64 = expected.iter().zip(&wins).map(|(e, &w)| (e - w as f64).abs()).sum();
+
+    let buckets = (0..bucket_count).map(|i| {
+        let (observed_low, observed_high) = wilson_interval(wins[i], played[i]);
+        CalibrationBucket {
+            from: i as f64 / bucket_count as f64,
+            to: (i + 1) as f64 / bucket_count as f64,
+            predictions: played[i],
+            wins: wins[i],
+            expected: if played[i] > 0 { expected[i] / played[i] as f64 } else { 0.0 },
+            observed: if played[i] > 0 { wins[i] as f64 / played[i] as f64 } else { 0.0 },
+            observed_low,
+            observed_high,
+        }
+    }).collect();
+
+    if predictions.is_empty() {
+        return Scores { predictions: 0, log_loss: 0.0, brier: 0.0, accuracy: 0.0, auc: None, calibration_error: 0.0, buckets };
+    }
+
+    Scores {
+        predictions: predictions.len(),
+        log_loss: log_loss / n,
+        brier: brier / n,
+        accuracy: correct / n,
+        auc: auc(predictions),
+        calibration_error: calibration_error / n,
+        buckets,
+    }
+}
+
+/// 95% Wilson score interval for a win rate. (0, 1) if nothing was played
+pub fn wilson_interval(wins: usize, played: usize) -> (f64, f64) {
+    if played == 0 {
+        return (0.0, 1.0);
+    }
+
+    let n = played as f64;
+    let rate = wins as f64 / n;
+    let z2 = WILSON_Z * WILSON_Z;
+
+    let center = (rate + z2 / (2.0 * n)) / (1.0 + z2 / n);
+    let margin = WILSON_Z / (1.0 + z2 / n) * (rate * (1.0 - rate) / n + z2 / (4.0 * n * n)).sqrt();
+
+    ((center - margin).max(0.0), (center + margin).min(1.0))
+}
+
+// Mann-Whitney U: the ranks of the wins among all predictions, with ties sharing the average rank
+fn auc(predictions: &[Prediction]) -> Option<f64> {
+    let positives = predictions.iter().filter(|p| p.outcome).count();
+    let negatives = predictions.len() - positives;
+    if positives == 0 || negatives == 0 {
+        return None;
+    }
+
+    let mut sorted: Vec<&Prediction> = predictions.iter().collect();
+    sorted.sort_by(|a, b| a.probability.total_cmp(&b.probability));
+
+    let mut positive_rank_sum = 0.0;
+    let mut i = 0;
+    while i < sorted.len() {
+        let mut j = i;
+        while j < sorted.len() && sorted[j].probability == sorted[i].probability {
+            j += 1;
+        }
+
+        // Ranks i + 1 to j
+        let average_rank = (i + 1 + j) as f64 / 2.0;
+        positive_rank_sum += average_rank * sorted[i..j].iter().filter(|p| p.outcome).count() as f64;
+        i = j;
+    }
+
+    let positives = positives as f64;
+    Some((positive_rank_sum - positives * (positives + 1.0) / 2.0) / (positives * negatives as f64))
+}
+
+/// Team 1's chance of winning each match, from the teams' current ratings as system sees them. Matches against a
+/// team that isn't in the list are left out, so the teams can be filtered first.
+pub fn match_predictions(teams: &[Team], matches: &[Match], ranking_context: &RankingContext, system: &dyn RatingSystem) -> Result<Vec<Prediction>> {
+    let teams = TeamIndex::new(teams);
+
+    Ok(matches.iter().filter_map(|m| {
+        let (team_1, team_2) = teams.pair(m)?;
+        Some(Prediction {
+            match_start_time: m.match_start_time,
+            probability: system.predict(team_1, team_2, ranking_context),
+            outcome: m.winner == m.team_1,
+        })
+    }).collect())
+}
+
+/// Same as match_predictions, but for every map. Tied maps are left out
+pub fn map_predictions(teams: &[Team], matches: &[Match], ranking_context: &RankingContext, system: &dyn RatingSystem) -> Result<Vec<Prediction>> {
+    let teams = TeamIndex::new(teams);
+
+    let mut predictions = Vec::new();
+    for m in matches {
+        let Some((team_1, team_2)) = teams.pair(m) else { continue };
+        let probability = system.predict(team_1, team_2, ranking_context);
+        for map in m.maps.iter().filter(|map| map.team_1_score != map.team_2_score) {
+            predictions.push(Prediction {
+                match_start_time: m.match_start_time,
+                probability,
+                outcome: map.team_1_score > map.team_2_score,
+            });
+        }
+    }
+    Ok(predictions)
+}
+
+/// Scores the teams' current ratings on the matches, per match and per map. Like analyze_fit, this is in-sample
+/// unless the ratings came from other matches. See backtest.rs.
+pub fn evaluate(teams: &[Team], matches: &[Match], ranking_context: &RankingContext, system: &dyn RatingSystem, bucket_count: usize) -> Result<Evaluation> {
+    Ok(Evaluation {
+        matches: score(&match_predictions(teams, matches, ranking_context, system)?, bucket_count),
+        maps: score(&map_predictions(teams, matches, ranking_context, system)?, bucket_count),
+    })
+}
+
+impl fmt::Display for Scores {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in self.buckets.iter().filter(|b| b.predictions > 0) {
+            writeln!(f, "  {0:3.2}-{1:3.2} | Predictions: {2:5} | Expected: {3:4.2} | Observed: {4:4.2} ({5:4.2}-{6:4.2})",
+                b.from, b.to, b.predictions, b.expected, b.observed, b.observed_low, b.observed_high)?;
+        }
+
+        let auc = self.auc.map_or("-".to_string(), |auc| format!("{auc:.4}"));
+        writeln!(f, "  Predictions {}, log loss {:.4}, Brier score {:.4}, accuracy {:.4}, AUC {auc}, calibration error {:.4}",
+            self.predictions, self.log_loss, self.brier, self.accuracy, self.calibration_error)
+    }
+}
+
+impl fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Per match:")?;
+        write!(f, "{}", self.matches)?;
+        writeln!(f, "Per map:")?;
+        write!(f, "{}", self.maps)
+    }
+}
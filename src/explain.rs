@@ -0,0 +1,279 @@
+use serde::*;
+use std::fmt;
+use crate::data_loader::*;
+use crate::error::*;
+use crate::ranking::*;
+use crate::ranking_context::RankingContext;
+use crate::rating_system::require_alternative_vrs;
+use crate::registry::*;
+use crate::util::format_date;
+
+/// Everything that went into one team's rating, from the events behind its factors to every map it played.
+/// See explain_team.
+#[derive(Serialize, Debug, Clone)]
+pub struct TeamExplanation {
+    pub team: TeamId,
+    pub name: String,
+
+    /// Every event the team placed at, which is what Prize Money and Event Participation are built from
+    pub events: Vec<EventContribution>,
+    pub prize_money: ScaledFactor,
+    pub event_participation: ScaledFactor,
+
+    /// The opponents counted for Opponent Winnings and Opponent Network, best first
+    pub opponent_winnings: OpponentFactor,
+    pub opponent_network: OpponentFactor,
+
+    pub seed: SeedBreakdown,
+
+    /// Every map the team played, oldest first. Taken from the Elo ledger, see map_elo_adjustments_with_ledger
+    pub maps: Vec<MapEloChange>,
+    pub elo: f64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct EventContribution {
+    pub event: EventId,
+    pub name: String,
+    pub placement: u32,
+    pub prize: f64,
+    pub prize_pool: f64,
+    pub last_match_time: u32,
+    /// How much the event still counts, between 0 and 1. See RankingContext::time_mod
+    pub time_decay: f64,
+    /// sqrt(prize) * time_decay
+    pub prize_money: f64,
+    /// log10(prize_pool) * time_decay
+    pub event_participation: f64,
+}
+
+/// A factor that's the sum of something, scaled so the top_outlier_count-th best team gets 1.0
+#[derive(Serialize, Debug, Clone)]
+pub struct ScaledFactor {
+    pub sum: f64,
+    pub reference: f64,
+    pub value: f64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct OpponentContribution {
+    pub team: TeamId,
+    pub name: String,
+    pub match_start_time: u32,
+    pub information_context: f64,
+    /// The opponent's prize money or own network, times the information context of the match
+    pub value: f64,
+}
+
+/// A factor built from the best factor_bucket_size wins, scaled to the reference team and then curved
+#[derive(Serialize, Debug, Clone)]
+pub struct OpponentFactor {
+    pub counted: Vec<OpponentContribution>,
+    pub sum: f64,
+    pub reference: f64,
+    pub scaled: f64,
+    pub value: f64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SeedBreakdown {
+    /// (factor, value, weight)
+    pub factors: Vec<(String, f64, f64)>,
+    pub sum_of_factors: f64,
+    /// The lowest and highest sum of any team, which are remapped to min_seeded_rank and max_seeded_rank
+    pub lowest_sum_of_factors: f64,
+    pub highest_sum_of_factors: f64,
+    pub min_seeded_rank: f64,
+    pub max_seeded_rank: f64,
+    pub seed_points: f64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct MapEloChange {
+    pub match_start_time: u32,
+    pub opponent: TeamId,
+    pub opponent_name: String,
+    pub map_name: String,
+    /// Rounds for this team first
+    pub score: (u16, u16),
+    pub elo_before: f64,
+    pub opponent_elo_before: f64,
+    pub change: f64,
+    pub elo_after: f64,
+}
+
+/// Explains a single team's rating. Runs the ranking again on a copy of the teams, so it doesn't matter whether
+/// gen_rank_new has been run on them, and the teams are left as they are. Only the Alternative VRS can be explained.
+pub fn explain_team(matches: &[Match], events: &[Event], teams: &[Team], ranking_context: &RankingContext, team: TeamId) -> Result<TeamExplanation> {
+    require_alternative_vrs(ranking_context, "explain")?;
+
+    let mut teams = teams_by_id(teams, matches)?.into_owned();
+    if team.0 >= teams.len() {
+        return Err(Error::MissingTeam(team));
+    }
+
+    let references = seed_teams(matches, events, &mut teams, ranking_context)?;
+    let t = &teams[team.0];
+
+    // Prize Money and Event Participation
+    let mut event_contributions = Vec::new();
+    for ev in events {
+        for pd in ev.prize_distribution.iter().filter(|pd| pd.team == Some(team)) {
+            let time_decay = ranking_context.time_mod(ev.last_match_time);
+
+            event_contributions.push(EventContribution {
+                event: ev.id,
+                name: ev.name.clone(),
+                placement: pd.placement,
+                prize: pd.prize,
+                prize_pool: ev.prize_pool,
+                last_match_time: ev.last_match_time,
+                time_decay,
+                prize_money: pd.prize.sqrt() * time_decay,
+                event_participation: ev.prize_pool.max(1.0).log10() * time_decay,
+            });
+        }
+    }
+
+    let prize_money = ScaledFactor {
+        sum: t.adjusted_winnings,
+        reference: references.winnings,
+        value: t.prize_money,
+    };
+    let event_participation = ScaledFactor {
+        sum: event_contributions.iter().map(|e| e.event_participation).sum(),
+        reference: references.event_participation,
+        value: t.event_participation,
+    };
+
+    // Opponent Winnings and Opponent Network, from every win
+    let mut winnings = Vec::new();
+    let mut networks = Vec::new();
+    for m in matches.iter().filter(|m| m.winner == team) {
+        let opponent = &teams[m.other_team(team).0];
+        let contribution = |value: f64| OpponentContribution {
+            team: opponent.id,
+            name: opponent.name.clone(),
+            match_start_time: m.match_start_time,
+            information_context: m.information_context,
+            value: value * m.information_context,
+        };
+
+        winnings.push(contribution(opponent.adjusted_winnings));
+        networks.push(contribution(opponent.own_network));
+    }
+
+    let opponent_winnings = opponent_factor(winnings, references.opponent_winnings, t.opponent_winnings, ranking_context);
+    let opponent_network = opponent_factor(networks, references.opponent_network, t.opponent_network, ranking_context);
+
+    let seed = SeedBreakdown {
+        factors: vec![
+            ("Prize Money".to_string(), t.prize_money, ranking_context.prize_money_weight),
+            ("Opponent Winnings".to_string(), t.opponent_winnings, ranking_context.opponet_winnings_weight),
+            ("Event Participation".to_string(), t.event_participation, ranking_context.event_participation_weight),
+            ("Opponent Network".to_string(), t.opponent_network, ranking_context.opponent_network_weight),
+        ],
+        sum_of_factors: t.sum_of_factors,
+        lowest_sum_of_factors: references.lowest_sum_of_factors,
+        highest_sum_of_factors: references.highest_sum_of_factors,
+        min_seeded_rank: ranking_context.min_seeded_rank,
+        max_seeded_rank: ranking_context.max_seeded_rank,
+        seed_points: t.seed_points,
+    };
+
+    let name = t.name.clone();
+    let ledger = map_elo_adjustments_with_ledger(matches, &mut teams, ranking_context)?;
+    let maps = ledger.entries_for(team).map(|e| {
+        let (elo_before, change) = e.for_team(team).unwrap_or_default();
+        let (opponent, score, opponent_elo_before) = if e.team_1 == team {
+            (e.team_2, (e.team_1_score, e.team_2_score), e.team_2_elo_before)
+        } else {
+            (e.team_1, (e.team_2_score, e.team_1_score), e.team_1_elo_before)
+        };
+
+        MapEloChange {
+            match_start_time: e.match_start_time,
+            opponent,
+            opponent_name: teams[opponent.0].name.clone(),
+            map_name: e.map_name.clone(),
+            score,
+            elo_before,
+            opponent_elo_before,
+            change,
+            elo_after: elo_before + change,
+        }
+    }).collect();
+
+    Ok(TeamExplanation {
+        team,
+        name,
+        events: event_contributions,
+        prize_money,
+        event_participation,
+        opponent_winnings,
+        opponent_network,
+        seed,
+        maps,
+        elo: teams[team.0].elo,
+    })
+}
+
+// Same as sum_of_nth_best in ranking.rs, keeping track of who the values came from
+fn opponent_factor(mut contributions: Vec<OpponentContribution>, reference: f64, value: f64, ranking_context: &RankingContext) -> OpponentFactor {
+    contributions.sort_by(|a, b| b.value.total_cmp(&a.value));
+    contributions.truncate(ranking_context.factor_bucket_size);
+
+    let sum = contributions.iter().map(|c| c.value).sum();
+    OpponentFactor {
+        counted: contributions,
+        sum,
+        reference,
+        scaled: scale_to_reference(sum, reference),
+        value,
+    }
+}
+
+impl fmt::Display for TeamExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} (team {})", self.name, self.team)?;
+        writeln!(f)?;
+
+        writeln!(f, "Events, for Prize Money and Event Participation:")?;
+        for e in &self.events {
+            writeln!(f, "  {0:30} | {1} | place {2:2} | prize {3:9.0} of {4:9.0} | decay {5:.3} | PM +{6:7.2} | EP +{7:.3}",
+                e.name, format_date(e.last_match_time), e.placement, e.prize, e.prize_pool, e.time_decay, e.prize_money, e.event_participation)?;
+        }
+        writeln!(f, "  Prize Money: {:.2} / reference {:.2} = {:.3}", self.prize_money.sum, self.prize_money.reference, self.prize_money.value)?;
+        writeln!(f, "  Event Participation: {:.3} / reference {:.3} = {:.3}",
+            self.event_participation.sum, self.event_participation.reference, self.event_participation.value)?;
+        writeln!(f)?;
+
+        for (title, factor) in [("Opponent Winnings", &self.opponent_winnings), ("Opponent Network", &self.opponent_network)] {
+            writeln!(f, "{title}, from the best wins:")?;
+            for c in &factor.counted {
+                writeln!(f, "  {0:20} | {1} | info {2:.3} | {3:.3}", c.name, format_date(c.match_start_time), c.information_context, c.value)?;
+            }
+            writeln!(f, "  {title}: {:.3} / reference {:.3} = {:.3}, curved to {:.3}", factor.sum, factor.reference, factor.scaled, factor.value)?;
+            writeln!(f)?;
+        }
+
+        writeln!(f, "Seed:")?;
+        for (factor, value, weight) in &self.seed.factors {
+            writeln!(f, "  {factor:20} {value:.3} x {weight}")?;
+        }
+        writeln!(f, "  Sum of factors {:.3}, remapped from {:.3}..{:.3} to {}..{} = {:.1} seed points",
+            self.seed.sum_of_factors,
+            self.seed.lowest_sum_of_factors, self.seed.highest_sum_of_factors,
+            self.seed.min_seeded_rank, self.seed.max_seeded_rank,
+            self.seed.seed_points)?;
+        writeln!(f)?;
+
+        writeln!(f, "Maps:")?;
+        for m in &self.maps {
+            writeln!(f, "  {0} | {1:20} | {2:12} {3:2}-{4:<2} | {5:6.1} vs {6:6.1} | {7:+6.1} => {8:6.1}",
+                format_date(m.match_start_time), m.opponent_name, m.map_name, m.score.0, m.score.1,
+                m.elo_before, m.opponent_elo_before, m.change, m.elo_after)?;
+        }
+        writeln!(f, "Final Elo {:.1}", self.elo)
+    }
+}
@@ -0,0 +1,168 @@
+use serde::*;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use crate::data_loader::*;
+use crate::error::*;
+use crate::ranking_context::RankingContext;
+use crate::regions::Region;
+use crate::registry::TeamId;
+use crate::report::{ranked_teams, ranking_eligible};
+use crate::util::format_date;
+
+/// The full standings after gen_rank_new, ready to be written as JSON or CSV.
+/// Every team is in there, sorted by Elo. Teams that aren't ranking eligible don't get a rank, and tied teams share
+/// one, the same way the reports do.
+#[derive(Serialize, Debug, Clone)]
+pub struct Standings {
+    pub metadata: StandingsMetadata,
+    pub standings: Vec<StandingsRow>,
+}
+
+/// What the standings were built from
+#[derive(Serialize, Debug, Clone)]
+pub struct StandingsMetadata {
+    pub version: String,
+    pub data_file: String,
+    pub window_start: String,
+    pub window_end: String,
+    pub ranking_context: RankingContext,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct StandingsRow {
+    pub rank: Option<usize>,
+    pub team_id: TeamId,
+    pub name: String,
+    pub region: Option<Region>,
+    pub core: Vec<String>,
+
+    pub elo: f64,
+    pub seed_points: f64,
+    pub rating_deviation: Option<f64>,
+
+    pub prize_money: f64,
+    pub opponent_winnings: f64,
+    pub event_participation: f64,
+    pub opponent_network: f64,
+    pub own_network: f64,
+
+    pub matches_played: u32,
+    pub matches_won: u32,
+    pub eligible: bool,
+}
+
+impl Standings {
+    pub fn new(teams: &[Team], ranking_context: &RankingContext, data_file: &str) -> Self {
+        let ranks: HashMap<TeamId, usize> = ranked_teams(teams, ranking_context).into_iter().map(|(rank, t)| (t.id, rank)).collect();
+
+        let mut sorted: Vec<&Team> = teams.iter().collect();
+        sorted.sort_by(|a, b| b.elo.total_cmp(&a.elo));
+
+        let standings = sorted.into_iter().map(|t| {
+            StandingsRow {
+                rank: ranks.get(&t.id).copied(),
+                team_id: t.id,
+                name: t.name.clone(),
+                region: t.region,
+                core: t.core.iter().map(|p| p.nick.clone()).collect(),
+
+                elo: t.elo,
+                seed_points: t.seed_points,
+                rating_deviation: t.rating_deviation,
+
+                prize_money: t.prize_money,
+                opponent_winnings: t.opponent_winnings,
+                event_participation: t.event_participation,
+                opponent_network: t.opponent_network,
+                own_network: t.own_network,
+
+                matches_played: t.matches_played,
+                matches_won: t.matches_won,
+                eligible: ranking_eligible(t, ranking_context),
+            }
+        }).collect();
+
+        Self {
+            metadata: StandingsMetadata {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                data_file: data_file.to_string(),
+                window_start: format_date(ranking_context.time_window_start),
+                window_end: format_date(ranking_context.time_window_end),
+                ranking_context: ranking_context.clone(),
+            },
+            standings,
+        }
+    }
+
+    pub fn write_json<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writeln!(writer)?;
+        writer.flush()
+    }
+
+    /// Just the metadata, as JSON. export_standings writes it next to the CSV, which has no place for it
+    pub fn write_metadata<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut writer, &self.metadata)?;
+        writeln!(writer)?;
+        writer.flush()
+    }
+
+    /// One row per team, under a single header row. The metadata isn't in there, see write_metadata
+    pub fn write_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut csv_writer = csv::Writer::from_writer(writer);
+        csv_writer.write_record([
+            "rank", "team_id", "name", "region", "core_1", "core_2", "core_3", "core_4", "core_5",
+            "elo", "seed_points", "rating_deviation", "prize_money", "opponent_winnings", "event_participation", "opponent_network", "own_network",
+            "matches_played", "matches_won", "eligible",
+        ])?;
+
+        for row in &self.standings {
+            let mut record = vec![
+                row.rank.map(|r| r.to_string()).unwrap_or_default(),
+                row.team_id.to_string(),
+                row.name.clone(),
+                row.region.map(|r| r.to_string()).unwrap_or_default(),
+            ];
+            record.extend((0..5).map(|idx| row.core.get(idx).cloned().unwrap_or_default()));
+            record.extend([row.elo.to_string(), row.seed_points.to_string(), row.rating_deviation.map(|d| d.to_string()).unwrap_or_default()]);
+            record.extend([
+                row.prize_money, row.opponent_winnings, row.event_participation, row.opponent_network, row.own_network,
+            ].map(|value| value.to_string()));
+            record.extend([row.matches_played.to_string(), row.matches_won.to_string(), row.eligible.to_string()]);
+
+            csv_writer.write_record(&record)?;
+        }
+
+        csv_writer.flush()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+/// Writes the standings to a file. A CSV gets its metadata in a JSON file next to it, see metadata_path
+pub fn export_standings(standings: &Standings, path: &str, format: ExportFormat) -> Result<()> {
+    let write = |path: &str, write: &dyn Fn(io::BufWriter<File>) -> io::Result<()>| {
+        File::create(path)
+            .and_then(|file| write(io::BufWriter::new(file)))
+            .map_err(|source| Error::Write { path: path.to_string(), source })
+    };
+
+    match format {
+        ExportFormat::Json => write(path, &|file| standings.write_json(file)),
+        ExportFormat::Csv => {
+            write(path, &|file| standings.write_csv(file))?;
+            write(&metadata_path(path), &|file| standings.write_metadata(file))
+        }
+    }
+}
+
+/// Where export_standings puts the metadata of a CSV: standings.csv gets standings.meta.json
+pub fn metadata_path(path: &str) -> String {
+    Path::new(path).with_extension("meta.json").to_string_lossy().into_owned()
+}
@@ -0,0 +1,217 @@
+use serde::Serialize;
+use crate::*;
+use crate::registry::TeamIndex;
+
+/// Checks error while stepping the Event Participation weight, in step order. See Sweep for any other field, or
+/// several at once.
+pub fn ranking_var_checker(dataset: &Dataset, ranking_context: RankingContext, from: f64, to: f64, step: f64) -> Result<()> {
+    let parameter = ParameterRange { field: "event_participation_weight".to_string(), from, to, step };
+    let mut sweep = Sweep::grid(vec![parameter]).run(dataset, &ranking_context)?;
+    sweep.results.sort_by(|a, b| a.values[0].total_cmp(&b.values[0]));
+
+    for r in &sweep.results {
+        println!("Weight {0:2.1}, error {1:5.4}", r.values[0], r.error);
+    }
+    for f in &sweep.failed {
+        println!("Weight {0:2.1}, failed: {1}", f.values[0], f.reason);
+    }
+
+    Ok(())
+}
+
+/// Runs two models on the same data and reports the fit of both, baseline first
+pub fn compare_fit(dataset: &Dataset, baseline: &RankingContext, candidate: &RankingContext) -> Result<()> {
+    for (label, ranking_context) in [("Baseline", baseline), ("Candidate", candidate)] {
+        let (matches, events, mut teams) = dataset.window(ranking_context)?;
+
+        rank(&matches, &events, &mut teams, ranking_context)?;
+
+        println!("{label} model:");
+        analyze_fit(&teams, &matches, ranking_context, true)?;
+        println!();
+    }
+
+    Ok(())
+}
+
+/// One bucket of analyze_fit: the matches where the underdog's expected win rate was between from and from + 0.1,
+/// counted once for each team, so the favourites of the mirrored bucket are in here too.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct FitBucket {
+    pub from: f64,
+    pub played: f64,
+    pub wins: f64,
+    pub expected_wins: f64,
+}
+
+impl FitBucket {
+    pub fn win_rate(&self) -> f64 {
+        self.wins / self.played
+    }
+
+    pub fn expected_win_rate(&self) -> f64 {
+        self.expected_wins / self.played
+    }
+}
+
+/// The underdog half of analyze_fit's buckets, and the error they add up to
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Fit {
+    pub buckets: Vec<FitBucket>,
+    pub error: f64,
+}
+
+/// Finds difference between actual and expected win rate. Returns average error per match.
+/// The expected win rates come from the rating system in ranking_context, so each model is judged by its own odds.
+/// The teams can be sorted or filtered. Matches against a team that isn't in the list are left out.
+pub fn analyze_fit(teams: &[Team], matches: &[Match], ranking_context: &RankingContext, verbose: bool) -> Result<f64> {
+    let fit = fit_by_bucket(teams, matches, ranking_context)?;
+
+    if verbose {
+        for b in &fit.buckets {
+            println!("EWR Bucket {0:3.2} | Matches played: {1:5} | Win rate: {2:4.2} | ExWR: {3:4.2}",
+                b.from,
+                b.played,
+                b.win_rate(),
+                b.expected_win_rate(),
+            );
+        }
+        println!("Total error from this model: {}", fit.error);
+    }
+
+    Ok(fit.error)
+}
+
+/// Same as analyze_fit, with every bucket
+pub fn fit_by_bucket(teams: &[Team], matches: &[Match], ranking_context: &RankingContext) -> Result<Fit> {
+    let teams = TeamIndex::new(teams);
+    let system = ranking_context.rating_system.system();
+
+    const BUCKET_SIZE: usize = 10;
+
+    let mut bucket_wins = [0.0;BUCKET_SIZE];
+    let mut bucket_ewins = [0.0;BUCKET_SIZE];
+    let mut bucket_played = [0.0;BUCKET_SIZE];
+
+    for m in matches {
+        let (Some(winner), Some(loser)) = (teams.get(m.winner), teams.get(m.loser())) else { continue };
+        let ewr = system.predict(winner, loser, ranking_context);
+
+        // A certain win would land in a bucket of its own
+        let bucket_index = ((ewr * BUCKET_SIZE as f64).floor() as usize).min(BUCKET_SIZE - 1);
+
+        bucket_wins[bucket_index] += 1.0;
+        bucket_played[bucket_index] += 1.0;
+        bucket_played[BUCKET_SIZE - 1 - bucket_index] += 1.0;
+        bucket_ewins[bucket_index] += ewr;
+        bucket_ewins[BUCKET_SIZE - 1 - bucket_index] += 1.0 - ewr;
+    }
+
+    let mut error = 0.0;
+    let mut buckets = Vec::new();
+
+    let mut sum_matches_played: f64 = 0.0;
+    for i in 0..(BUCKET_SIZE / 2) {
+        error += (bucket_wins[i] - bucket_ewins[i]).abs();
+
+        sum_matches_played += bucket_played[i];
+
+        buckets.push(FitBucket {
+            from: i as f64 / BUCKET_SIZE as f64,
+            played: bucket_played[i],
+            wins: bucket_wins[i],
+            expected_wins: bucket_ewins[i],
+        });
+    }
+
+    error /= sum_matches_played;
+
+    Ok(Fit { buckets, error })
+}
+
+/// Error for each team. analyze_fit can score bad models well if those models are egregiously over/underranking some teams.
+/// This complies error for every team and reports the worst offenders, as well as a sample of other teams for comparsions.
+/// Teams without a single match against another team in the list are left out.
+pub fn team_fit(teams: &[Team], matches: &[Match], ranking_context: &RankingContext, verbose: bool) -> Result<Vec<(f64, f64)>> {
+    struct TeamDiff {
+        idx: usize,
+        abs_diff: f64,
+        net_diff: f64,
+    }
+
+    fn report_team_fit(td: &TeamDiff, teams: &[Team]) {
+        println!("{0:25} | Elo {1:6.1} | Absolute Diff. {2:6.2} | Net Diff. {3:6.2} | Matches PLayed {4:5}",
+            teams[td.idx].name,
+            teams[td.idx].elo,
+            td.abs_diff,
+            td.net_diff,
+            teams[td.idx].matches_played,
+        );
+    }
+
+    let index = TeamIndex::new(teams);
+    let system = ranking_context.rating_system.system();
+    let mut team_diffs: Vec<TeamDiff> = Vec::new();
+
+    for (idx, t) in teams.iter().enumerate() {
+        //if !t.ranking_eligible() { continue; }
+
+        let mut team_diff = TeamDiff {
+            idx,
+            abs_diff: 0.0,
+            net_diff: 0.0,
+        };
+
+        let mut played = 0.0;
+        for m in matches {
+            if !m.is_in_game(t.id) { continue; }
+            let Some(opponent) = index.get(m.other_team(t.id)) else { continue };
+
+            let ewr = system.predict(t, opponent, ranking_context);
+            let actual = if m.winner == t.id { 1.0 } else { 0.0 };
+
+            team_diff.abs_diff += (ewr - actual).abs();
+            team_diff.net_diff += ewr - actual;
+            played += 1.0;
+        }
+
+        // With a filtered team list some teams have no opponents left, same as the matches analyze_fit skips
+        if played == 0.0 { continue; }
+
+        team_diff.abs_diff /= played;
+        team_diff.net_diff /= played;
+
+        team_diffs.push(team_diff);
+    }
+
+    let mut out = Vec::new();
+    for i in &team_diffs {
+        out.push( (i.abs_diff, i.net_diff) );
+    }
+
+    if verbose {
+        let reported_team_names = [
+            String::from("Vitality"),
+            String::from("ENCE"),
+            String::from("M80"),
+            String::from("Nigma Galaxy"),
+            String::from("ORKS"),
+            String::from("BetBoom"),
+            String::from("TRAFFIC Tashkent"),
+        ];
+    
+        for td in &team_diffs {
+            if reported_team_names.contains(&teams[td.idx].name) {
+                report_team_fit(td, teams);
+            }
+        }
+    
+        team_diffs.sort_by(|a, b| a.abs_diff.total_cmp(&b.abs_diff));
+        if let (Some(best), Some(worst)) = (team_diffs.first(), team_diffs.last()) {
+            report_team_fit(best, teams);
+            report_team_fit(worst, teams);
+        }
+    }
+
+    Ok(out)
+} 
@@ -0,0 +1,153 @@
+use serde::*;
+use std::io::{self, Write};
+use crate::data_loader::*;
+use crate::error::*;
+use crate::ranking_context::RankingContext;
+use crate::rating_system::rank;
+use crate::report::ranked_teams;
+use crate::util::format_date;
+
+const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+
+/// The standings at a series of cutoff dates, in long format: one point per ranked team per cutoff.
+/// Team IDs are only meaningful within one cutoff, since every cutoff windows its own teams, so teams are identified
+/// by name and HLTV IDs.
+#[derive(Serialize, Debug, Clone)]
+pub struct StandingsHistory {
+    pub window_days: u32,
+    pub ranking_context: RankingContext,
+    pub points: Vec<HistoryPoint>,
+    /// Cutoffs with too little data in the window to rank, or a window the ranking context refuses, and why
+    pub skipped: Vec<(u32, String)>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct HistoryPoint {
+    pub cutoff: u32,
+    pub name: String,
+    pub hltv_ids: Vec<usize>,
+    pub rank: usize,
+    pub elo: f64,
+    pub seed_points: f64,
+    pub matches_played: u32,
+}
+
+/// Weekdays for lining cutoffs up with, e.g. every Monday
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    fn of(timestamp: u32) -> u32 {
+        // 1970-01-01 was a Thursday
+        (timestamp / SECONDS_PER_DAY + 3) % 7
+    }
+
+    fn index(self) -> u32 {
+        self as u32
+    }
+}
+
+/// Cutoffs from from to to (both included) every step_days days, at midnight UTC. With a weekday, the first cutoff
+/// moves forward to the first such day on or after from.
+pub fn cutoffs(from: u32, to: u32, step_days: u32, weekday: Option<Weekday>) -> Vec<u32> {
+    let mut cutoff = from - from % SECONDS_PER_DAY;
+    if let Some(weekday) = weekday {
+        cutoff = cutoff.saturating_add((weekday.index() + 7 - Weekday::of(cutoff)) % 7 * SECONDS_PER_DAY);
+    }
+
+    let step = step_days.max(1).saturating_mul(SECONDS_PER_DAY);
+    let mut cutoffs = Vec::new();
+    while cutoff <= to {
+        cutoffs.push(cutoff);
+        cutoff = match cutoff.checked_add(step) {
+            Some(next) => next,
+            None => break,
+        };
+    }
+    cutoffs
+}
+
+/// Ranks the data again at each cutoff, using the window_days days before it, and collects every ranked team.
+/// Everything but the time window comes from ranking_context.
+pub fn standings_history(dataset: &Dataset, ranking_context: &RankingContext, cutoffs: &[u32], window_days: u32) -> Result<StandingsHistory> {
+    let mut history = StandingsHistory {
+        window_days,
+        ranking_context: ranking_context.clone(),
+        points: Vec::new(),
+        skipped: Vec::new(),
+    };
+
+    for &cutoff in cutoffs {
+        let mut cutoff_context = ranking_context.clone();
+        cutoff_context.time_window_end = cutoff;
+        cutoff_context.time_window_start = cutoff.saturating_sub(window_days.saturating_mul(SECONDS_PER_DAY));
+
+        // Early in the data there might not be enough teams to scale the factors by, and the window can end up
+        // shorter than the grace period when the start is cut off at the beginning of time
+        let ranked = dataset.window(&cutoff_context).and_then(|(matches, events, mut teams)| {
+            rank(&matches, &events, &mut teams, &cutoff_context)?;
+            Ok(teams)
+        });
+        let teams = match ranked {
+            Ok(teams) => teams,
+            Err(e @ (Error::Numeric(_) | Error::Context(_))) => { history.skipped.push((cutoff, e.to_string())); continue; }
+            Err(e) => return Err(e),
+        };
+
+        for (rank, t) in ranked_teams(&teams, &cutoff_context) {
+            history.points.push(HistoryPoint {
+                cutoff,
+                name: t.name.clone(),
+                hltv_ids: t.hltv_ids.clone(),
+                rank,
+                elo: t.elo,
+                seed_points: t.seed_points,
+                matches_played: t.matches_played,
+            });
+        }
+    }
+
+    Ok(history)
+}
+
+impl StandingsHistory {
+    /// A single team's points, by name
+    pub fn team(&self, name: &str) -> Vec<&HistoryPoint> {
+        self.points.iter().filter(|p| p.name == name).collect()
+    }
+
+    pub fn write_json<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writeln!(writer)?;
+        writer.flush()
+    }
+
+    /// One row per point. HLTV IDs are separated by spaces
+    pub fn write_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut csv_writer = csv::Writer::from_writer(writer);
+        csv_writer.write_record(["cutoff_date", "cutoff", "rank", "name", "hltv_ids", "elo", "seed_points", "matches_played"])?;
+
+        for p in &self.points {
+            csv_writer.write_record([
+                format_date(p.cutoff),
+                p.cutoff.to_string(),
+                p.rank.to_string(),
+                p.name.clone(),
+                p.hltv_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(" "),
+                p.elo.to_string(),
+                p.seed_points.to_string(),
+                p.matches_played.to_string(),
+            ])?;
+        }
+
+        csv_writer.flush()
+    }
+}
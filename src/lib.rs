@@ -0,0 +1,76 @@
+//! Alternative version of Valve's Regional Standings (VRS) for Counter-Strike.
+//!
+//! Ranking a data set takes three steps: load the HLTV match data with a [`RankingContext`], run the ranking,
+//! and read the results off the teams.
+//!
+//! ```no_run
+//! use alternative_vrs::*;
+//!
+//! let mut ranking_context = RankingContext::default();
+//! ranking_context.time_window_end = 1693330518;
+//! ranking_context.time_window_start = 1693330518 - (6 * 30 * 24 * 60 * 60);
+//!
+//! let (matches, events, mut teams) = load_data("matchdata.json".to_string(), &ranking_context)?;
+//! gen_rank_new(&matches, &events, &mut teams, &ranking_context)?;
+//!
+//! for team in teams.iter().filter(|t| ranking_eligible(t, &ranking_context)) {
+//!     println!("{} {:.1}", team.name, team.elo);
+//! }
+//! # Ok::<(), alternative_vrs::Error>(())
+//! ```
+//!
+//! Every team and event gets a [`TeamId`] or [`EventId`] on top of its HLTV IDs, and a [`Registry`] maps between the two.
+//! The ranking functions look teams up by ID, so they need the full team list from [`load_data`], but it doesn't
+//! matter if it's been sorted in the meantime, and the list keeps its order. Everything that only reads the ratings,
+//! like [`analyze_fit`] and [`evaluate`], works on a filtered list too, and leaves out the matches of teams that
+//! aren't in it.
+//!
+//! To rank the same file more than once, say with different settings, load a [`Dataset`] once and call
+//! [`Dataset::window`] for each ranking context. That skips reading and parsing the file every time.
+
+pub mod alt_rankings;
+pub mod backtest;
+pub mod compare;
+pub mod core_matching;
+pub mod data_loader;
+pub mod elo_ledger;
+pub mod error;
+pub mod evaluation;
+pub mod explain;
+pub mod export;
+pub mod fit;
+pub mod history;
+pub mod original_vrs;
+pub mod ranking;
+pub mod ranking_context;
+pub mod rating_system;
+pub mod regions;
+pub mod registry;
+pub mod report;
+pub mod roster;
+pub mod sweep;
+pub mod util;
+pub mod validation;
+
+pub use error::{Error, Result};
+pub use alt_rankings::{gen_rank_org_rounds, gen_rank_pure_elo, gen_rank_pure_elo_map, OrgRounds, PureElo, PureEloMap};
+pub use backtest::{backtest, Backtest, BacktestFold};
+pub use compare::{compare_models, ComparedModel, Comparison, TeamComparison};
+pub use core_matching::{CoreAssignment, CoreDecision, CoreDecisionKind, CoreLog, CoreMatcher, CoreMatching, FirstSeenCore, RollingCore, WeightedCore};
+pub use data_loader::{load_data, load_data_with, latest_match_time, insert_team, Dataset, LoadedData, Event, Map, Match, Player, PrizeDist, Team};
+pub use evaluation::{evaluate, map_predictions, match_predictions, score, wilson_interval, CalibrationBucket, Evaluation, Prediction, Scores};
+pub use explain::{explain_team, EventContribution, MapEloChange, OpponentContribution, OpponentFactor, ScaledFactor, SeedBreakdown, TeamExplanation};
+pub use export::{export_standings, metadata_path, ExportFormat, Standings, StandingsMetadata, StandingsRow};
+pub use elo_ledger::{EloLedger, LedgerEntry};
+pub use history::{cutoffs, standings_history, HistoryPoint, StandingsHistory, Weekday};
+pub use original_vrs::{seed_vrs_teams, vrs_elo_result, vrs_win_probability, OriginalVrs};
+pub use ranking::{gen_rank_new, gen_rank_with_ledger, elo_result, win_probability, curve_function, map_elo_adjustments, map_elo_adjustments_with_ledger};
+pub use ranking_context::{ContextError, RankingContext};
+pub use rating_system::{rank, run_rating_system, AlternativeVrs, Glicko2, RatingSystem, RatingSystemKind};
+pub use regions::{assign_region, assign_regions, Region, RegionRule};
+pub use registry::{EventId, Registry, TeamId, TeamIndex};
+pub use roster::{Lineup, RosterChange, RosterHistory};
+pub use report::{html_report, markdown_report, output_report, print_regional_standings, print_to_console, ranked_teams, ranking_eligible};
+pub use sweep::{ParameterRange, Sweep, SweepFailure, SweepMode, SweepResult, SweepResults};
+pub use validation::{validate_data, ValidationReport};
+pub use fit::{analyze_fit, compare_fit, fit_by_bucket, ranking_var_checker, team_fit, Fit, FitBucket};
@@ -1,40 +1,251 @@
-#![allow(dead_code,unused_imports,unused)]
+mod cli;
 
-mod data_loader;
-mod ranking_context;
-mod ranking;
-mod util;
-mod test;
-mod report;
-
-use ranking_context::*;
-use data_loader::*;
-use ranking::*;
-use test::*;
-use report::*;
+use clap::Parser;
+use alternative_vrs::*;
+use cli::*;
 
 /*
-    Time Window is just set manually to encompass the first and last games in the sample dataset
-    You can adjust the model with RankingContext.
-*/ 
+    The time window defaults to the six months leading up to the last match in the data file.
+    You can adjust the model with the command line flags, which map onto RankingContext. See --help
+*/
 
 fn main() {
-    let mut ranking_context = RankingContext::default();
-    ranking_context.time_window_end = 1693330518;
-    ranking_context.time_window_start = 1693330518 - (6 * 30 * 24 * 60 * 60); // End time minus six months
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Rank { data, model, format, output } => {
+            let dataset = or_exit(Dataset::load(&data.data));
+            let ranking_context = or_exit(build_context(&data, &model, &dataset));
+            let (matches, events, mut teams) = or_exit(dataset.window(&ranking_context));
+
+            /*
+            Note that teams that haven't won a game or have played fewer than 10 are excluded from the ranking, but not from the vector.
+            Instead, we use the method team.is_ranking_eligible() to filter them out after the fact.
+            Importantly, analyze_fit does not filter out teams that aren't ranking eligible.
+            */
+
+            or_exit(rank(&matches, &events, &mut teams, &ranking_context));
+
+            match format {
+                OutputFormat::Table    if output.is_none() => output_report(teams, &ranking_context),
+                OutputFormat::Detailed if output.is_none() => print_to_console(teams, &ranking_context),
+                OutputFormat::Regional if output.is_none() => print_regional_standings(teams, &ranking_context),
+                OutputFormat::Table | OutputFormat::Detailed | OutputFormat::Regional => {
+                    or_exit(Err("--output only works with --format json, csv, markdown or html"))
+                }
+                // The metadata goes in a file next to the CSV, and there's no such thing on the console
+                OutputFormat::Csv if output.is_none() => or_exit(Err("--format csv needs --output, for the metadata file next to it")),
+
+                OutputFormat::Json | OutputFormat::Csv => {
+                    let standings = Standings::new(&teams, &ranking_context, &data.data);
+                    let export_format = if format == OutputFormat::Json { ExportFormat::Json } else { ExportFormat::Csv };
+
+                    match output {
+                        Some(path) => or_exit(export_standings(&standings, &path, export_format)),
+                        None => or_exit(standings.write_json(std::io::stdout().lock())),
+                    }
+                }
+
+                OutputFormat::Markdown | OutputFormat::Html => {
+                    let report = if format == OutputFormat::Markdown {
+                        markdown_report(&teams, &ranking_context)
+                    } else {
+                        html_report(&teams, &ranking_context)
+                    };
+
+                    match output {
+                        Some(path) => or_exit(std::fs::write(&path, report).map_err(|source| Error::Write { path, source })),
+                        None => print!("{report}"),
+                    }
+                }
+            }
+        }
+
+        Command::Fit { data, model, teams: report_teams, buckets } => {
+            let dataset = or_exit(Dataset::load(&data.data));
+            let ranking_context = or_exit(build_context(&data, &model, &dataset));
+            let (matches, events, mut teams) = or_exit(dataset.window(&ranking_context));
+
+            let system = or_exit(rank(&matches, &events, &mut teams, &ranking_context));
+
+            or_exit(analyze_fit(&teams, &matches, &ranking_context, true));
+            println!();
+            print!("{}", or_exit(evaluate(&teams, &matches, &ranking_context, system.as_ref(), buckets)));
+
+            if report_teams {
+                or_exit(team_fit(&teams, &matches, &ranking_context, true));
+            }
+        }
+
+        Command::Sweep { data, model, params, samples, seed, threads, format, output } => {
+            let dataset = or_exit(Dataset::load(&data.data));
+            let ranking_context = or_exit(build_context(&data, &model, &dataset));
+
+            let mut sweep = match samples {
+                Some(samples) => Sweep::random(params, samples, seed),
+                None => Sweep::grid(params),
+            };
+            if let Some(threads) = threads {
+                sweep.threads = threads;
+            }
+            let results = or_exit(sweep.run(&dataset, &ranking_context));
+
+            let write = |writer: &mut dyn std::io::Write| match format {
+                TableFormat::Table => write!(writer, "{results}"),
+                TableFormat::Csv   => results.write_csv(writer),
+                TableFormat::Json  => results.write_json(writer),
+            };
+            write_output(output, write);
+        }
+
+        Command::Validate { data, model, format } => {
+            let dataset = or_exit(Dataset::load(&data.data));
+            let ranking_context = or_exit(build_context(&data, &model, &dataset));
+
+            let report = dataset.validate(&ranking_context);
+            match format {
+                ReportFormat::Text => print!("{report}"),
+                ReportFormat::Json => println!("{}", or_exit(report.to_json())),
+            }
+        }
+
+        Command::Cores { data, model, format } => {
+            let dataset = or_exit(Dataset::load(&data.data));
+            let ranking_context = or_exit(build_context(&data, &model, &dataset));
+            let mut matcher = ranking_context.core_matching.matcher();
+            let (_, _, _, core_log) = or_exit(dataset.window_with(&ranking_context, matcher.as_mut()));
 
-    let (matches, events, mut teams) = load_data(
-        "../data/matchdata_sample_20230829.json".to_string(),
-        &ranking_context
-    );
+            match format {
+                ReportFormat::Text => print!("{core_log}"),
+                ReportFormat::Json => println!("{}", or_exit(serde_json::to_string_pretty(&core_log))),
+            }
+        }
 
-    /*
-    Note that teams that haven't won a game or have played fewer than 10 are excluded from the ranking, but not from the vector.
-    Instead, we use the method team.is_ranking_eligible() to filter them out after the fact.
-    Importantly, analyze_fit does not filter out teams that aren't ranking eligible.
-    */
+        Command::Explain { data, model, team, format } => {
+            let dataset = or_exit(Dataset::load(&data.data));
+            let ranking_context = or_exit(build_context(&data, &model, &dataset));
+            let (matches, events, teams) = or_exit(dataset.window(&ranking_context));
 
-    gen_rank_new(&matches, &events, &mut teams, &ranking_context);
+            let team = or_exit(find_team(&teams, &team));
+            let explanation = or_exit(explain_team(&matches, &events, &teams, &ranking_context, team));
+            match format {
+                ReportFormat::Text => print!("{explanation}"),
+                ReportFormat::Json => println!("{}", or_exit(serde_json::to_string_pretty(&explanation))),
+            }
+        }
+
+        Command::Ledger { data, model, team, output } => {
+            let dataset = or_exit(Dataset::load(&data.data));
+            let ranking_context = or_exit(build_context(&data, &model, &dataset));
+            let (matches, events, mut teams) = or_exit(dataset.window(&ranking_context));
+
+            let mut ledger = or_exit(gen_rank_with_ledger(&matches, &events, &mut teams, &ranking_context));
+            if let Some(team) = team {
+                let team = or_exit(find_team(&teams, &team));
+                ledger.entries.retain(|e| e.is_in_game(team));
+            }
+
+            write_output(output, |writer| ledger.write_csv(writer));
+        }
+
+        Command::History { data, model, from, to, step_days, weekday, window_days, format, output } => {
+            let ranking_context = or_exit(model_context(&model));
+
+            let dataset = or_exit(Dataset::load(&data));
+            let to = to.unwrap_or_else(|| or_exit(dataset.latest_match_time()));
+            let from = from.unwrap_or(to.saturating_sub(window_days.saturating_mul(24 * 60 * 60)));
+            let cutoffs = cutoffs(from, to, step_days, weekday);
+
+            let history = or_exit(standings_history(&dataset, &ranking_context, &cutoffs, window_days));
+            for (cutoff, reason) in &history.skipped {
+                eprintln!("Skipped {}: {reason}", alternative_vrs::util::format_date(*cutoff));
+            }
+
+            let write = |writer: &mut dyn std::io::Write| match format {
+                HistoryFormat::Csv  => history.write_csv(writer),
+                HistoryFormat::Json => history.write_json(writer),
+            };
+            write_output(output, write);
+        }
+
+        Command::Backtest { data, model, from, to, step_days, window_days, horizon_days, buckets, format, output } => {
+            let ranking_context = or_exit(model_context(&model));
+
+            let dataset = or_exit(Dataset::load(&data));
+            let to = to.unwrap_or_else(|| or_exit(dataset.latest_match_time()).saturating_sub(horizon_days.saturating_mul(24 * 60 * 60)));
+            let from = from.unwrap_or(to.saturating_sub(window_days.saturating_mul(24 * 60 * 60)));
+            let cutoffs = cutoffs(from, to, step_days, None);
+
+            let backtest = or_exit(backtest(&dataset, &ranking_context, &cutoffs, window_days, horizon_days, buckets));
+
+            let write = |writer: &mut dyn std::io::Write| match format {
+                TableFormat::Table => write!(writer, "{backtest}"),
+                TableFormat::Csv   => backtest.write_csv(writer),
+                TableFormat::Json  => backtest.write_json(writer),
+            };
+            write_output(output, write);
+        }
+
+        Command::Compare { data, model, against, systems, max_move, buckets, format, output } => {
+            let dataset = or_exit(Dataset::load(&data.data));
+            let ranking_context = or_exit(build_context(&data, &model, &dataset));
+
+            let models = if against.is_empty() && systems.is_empty() {
+                // In the configured model's window, which might come from --config, since the first model's is the one
+                // every model is ranked in
+                let default_context = RankingContext {
+                    time_window_start: ranking_context.time_window_start,
+                    time_window_end: ranking_context.time_window_end,
+                    ..RankingContext::default()
+                };
+
+                vec![("default".to_string(), default_context), ("configured".to_string(), ranking_context)]
+            } else {
+                let mut models = vec![("configured".to_string(), ranking_context.clone())];
+                for path in against {
+                    // compare_models puts it in the configured model's window, and validates it there
+                    let other = or_exit(RankingContext::from_file(&path));
+                    models.push((path, other));
+                }
+                for system in systems {
+                    let mut other = ranking_context.clone();
+                    other.rating_system = model.rating_system_kind(system, &ranking_context);
+                    or_exit(other.validate());
+                    models.push((system.name(), other));
+                }
+                models
+            };
+
+            let comparison = or_exit(compare_models(&dataset, &models, buckets, max_move));
+
+            let write = |writer: &mut dyn std::io::Write| match format {
+                TableFormat::Table => write!(writer, "{comparison}"),
+                TableFormat::Csv   => comparison.write_csv(writer),
+                TableFormat::Json  => comparison.write_json(writer),
+            };
+            write_output(output, write);
+        }
+    }
+}
+
+// Writes to the file, or the console if there isn't one
+fn write_output(output: Option<String>, write: impl Fn(&mut dyn std::io::Write) -> std::io::Result<()>) {
+    match output {
+        Some(path) => {
+            let file = or_exit(std::fs::File::create(&path).map_err(|source| Error::Write { path: path.clone(), source }));
+            or_exit(write(&mut std::io::BufWriter::new(file)).map_err(|source| Error::Write { path, source }));
+        }
+        None => or_exit(write(&mut std::io::stdout().lock())),
+    }
+}
 
-    output_report(&teams, &ranking_context);
+// Prints the error and quits. Everything the CLI can fail on is a user error, so there's no point in a backtrace
+fn or_exit<T, E: std::fmt::Display>(result: std::result::Result<T, E>) -> T {
+    match result {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
 }
@@ -0,0 +1,173 @@
+// This is the original VRS script, ported onto the same data as the Alternative VRS so the two can be compared.
+// It's mostly here for the curious reader, and as a baseline.
+
+use std::collections::HashMap;
+use crate::data_loader::*;
+use crate::error::*;
+use crate::ranking::{curve_function, nth_highest, scale_to_reference};
+use crate::ranking_context::RankingContext;
+use crate::rating_system::*;
+use crate::registry::TeamId;
+use crate::util::*;
+
+// VRS's Glicko-like update without a ratings deviation, with the constants already worked out. The old constants were
+//   q = ln(10) / 400, g = 1 / sqrt(1 + q² * 16875 / π²)
+//   ev = 1 / (1 + 10^(g * (loser - winner) / 400))
+//   q * g * (1 - ev) * info / (1 / 5625 + q² * g² * ev * (1 - ev) * info²)
+const VRS_BASE: f64 = 1.0056157171343996;
+const VRS_K: f64 = 31.500043764474583;
+const VRS_DAMPING: f64 = 0.17640049016245588;
+
+/// Elo gained by the winner (and lost by the loser) of a match, as VRS has it. info is the match's
+/// information_context, so older matches move less.
+pub fn vrs_elo_result(winner_elo: f64, loser_elo: f64, info: f64) -> f64 {
+    let ev = vrs_win_probability(winner_elo, loser_elo);
+    VRS_K * (1.0 - ev) * info / (1.0 + VRS_DAMPING * ev * (1.0 - ev) * info * info)
+}
+
+/// The chance a team with elo has of beating a team with opponent_elo, as VRS sees it
+pub fn vrs_win_probability(elo: f64, opponent_elo: f64) -> f64 {
+    1.0 / (1.0 + VRS_BASE.powf(opponent_elo - elo))
+}
+
+/// Seeds every team the way VRS does, from LAN Wins, Bounty Offered, Bounty Collected and Opponent Network.
+/// The factors go in the closest Team fields, so every report works with them:
+/// - Bounty Offered, the team's own curved prize money, in prize_money
+/// - Bounty Collected, the prize money of the teams it beat, in opponent_winnings
+/// - Opponent Network in opponent_network, and Own Network in own_network
+/// - LAN Wins in event_participation, which replaced it in the Alternative VRS
+///
+/// The weights are the ranking context's weights for those fields. VRS didn't weigh Own Network at all.
+/// The event prize pool is the one HLTV lists, like VRS had it, not the sum of the prize distribution.
+pub fn seed_vrs_teams(matches: &[Match], events: &[Event], teams: &mut [Team], ranking_context: &RankingContext) -> Result<()> {
+    // FaZe 1: Calculates LAN Wins, Own Network and Bounty Offered
+    let mut lan_wins = vec![0.0; teams.len()];
+    for (idx, team) in teams.iter_mut().enumerate() {
+        let mut wins = Vec::new();
+        // The latest win against each opponent. Information context only grows with time, so that's the highest
+        let mut opponents: HashMap<TeamId, f64> = HashMap::new();
+
+        for m in matches {
+            if m.winner != TeamId(idx) { continue; }
+
+            let info = opponents.entry(m.other_team(TeamId(idx))).or_insert(0.0);
+            *info = info.max(m.information_context);
+
+            if events[m.event.0].is_lan { wins.push(m.information_context); }
+        }
+
+        team.own_network = opponents.values().sum();
+        lan_wins[idx] = best_of(wins, ranking_context);
+    }
+
+    // BOUNTY OFFERED. The team's best prize moneys, one per event
+    let mut prize_moneys: Vec<Vec<f64>> = vec![Vec::new(); teams.len()];
+    for ev in events {
+        let mut paid: Vec<TeamId> = Vec::new();
+        for pd in &ev.prize_distribution {
+            let Some(team) = pd.team else { continue; };
+            if paid.contains(&team) { continue; }
+            paid.push(team);
+
+            prize_moneys[team.0].push(pd.prize * ranking_context.time_mod(ev.last_match_time));
+        }
+    }
+    for (team, prize_money) in teams.iter_mut().zip(prize_moneys) {
+        team.adjusted_winnings = best_of(prize_money, ranking_context);
+    }
+    for (team, &lan) in teams.iter_mut().zip(&lan_wins) {
+        team.event_participation = lan;
+    }
+
+    let reference_winnings = nth_highest(teams, ranking_context, "bounty offered", |t| t.adjusted_winnings)?;
+    let reference_network  = nth_highest(teams, ranking_context, "own network", |t| t.own_network)?;
+    let reference_lan_wins = nth_highest(teams, ranking_context, "LAN wins", |t| t.event_participation)?;
+
+    // Bounty Collected uses the bounty before it's curved
+    let mut uncurved_bounty_offered = vec![0.0; teams.len()];
+    for (idx, t) in teams.iter_mut().enumerate() {
+        uncurved_bounty_offered[idx] = scale_to_reference(t.adjusted_winnings, reference_winnings);
+        t.prize_money         = curve_function(uncurved_bounty_offered[idx]);
+        t.own_network         = scale_to_reference(t.own_network, reference_network);
+        t.event_participation = scale_to_reference(t.event_participation, reference_lan_wins);
+    }
+
+    // FaZe 2: Go through matches again to calculate Bounty Collected and Opponent Network. Both are scaled by the
+    // event prize pool as well
+    let bucket_size = ranking_context.factor_bucket_size.max(1) as f64;
+    for idx in 0..teams.len() {
+        let mut bounties = Vec::new();
+        let mut networks = Vec::new();
+
+        for m in matches {
+            if m.winner != TeamId(idx) { continue; }
+
+            let opp_id = m.other_team(TeamId(idx));
+            let event_prize_pool = f64::max(events[m.event.0].listed_prize_pool, 1.0);
+            let scaling = m.information_context * curve_function(f64::min(event_prize_pool / ranking_context.max_prize_pool_mod, 1.0));
+
+            bounties.push(uncurved_bounty_offered[opp_id.0] * scaling);
+            networks.push(teams[opp_id.0].own_network * scaling);
+        }
+
+        teams[idx].opponent_winnings = curve_function(best_of(bounties, ranking_context) / bucket_size);
+        teams[idx].opponent_network  = best_of(networks, ranking_context) / bucket_size;
+    }
+
+    // FaZe 3: We can finally calculate Starting Rating
+    let mut highest_sum_of_factors = f64::MIN;
+    let mut lowest_sum_of_factors  = f64::MAX;
+    for t in teams.iter_mut() {
+        t.sum_of_factors = t.opponent_winnings   * ranking_context.opponet_winnings_weight
+                         + t.prize_money         * ranking_context.prize_money_weight
+                         + t.opponent_network    * ranking_context.opponent_network_weight
+                         + t.event_participation * ranking_context.event_participation_weight;
+
+        highest_sum_of_factors = f64::max(highest_sum_of_factors, t.sum_of_factors);
+        lowest_sum_of_factors  = f64::min(lowest_sum_of_factors,  t.sum_of_factors);
+    }
+
+    for team in teams.iter_mut() {
+        team.seed_points = remap_value_clamped(team.sum_of_factors,
+            lowest_sum_of_factors,
+            highest_sum_of_factors,
+            ranking_context.min_seeded_rank,
+            ranking_context.max_seeded_rank
+        );
+
+        team.elo = team.seed_points;
+    }
+
+    Ok(())
+}
+
+// Sum of the factor_bucket_size best results
+fn best_of(mut results: Vec<f64>, ranking_context: &RankingContext) -> f64 {
+    results.sort_by(|a, b| b.total_cmp(a));
+    results.truncate(ranking_context.factor_bucket_size);
+    sum_vector(results)
+}
+
+/// The original VRS: seed_vrs_teams, then vrs_elo_result match by match
+pub struct OriginalVrs;
+
+impl RatingSystem for OriginalVrs {
+    fn kind(&self) -> RatingSystemKind {
+        RatingSystemKind::OriginalVrs
+    }
+
+    fn seed(&mut self, matches: &[Match], events: &[Event], teams: &mut [Team], ranking_context: &RankingContext) -> Result<()> {
+        seed_vrs_teams(matches, events, teams, ranking_context)
+    }
+
+    fn update_match(&mut self, m: &Match, teams: &mut [Team], _ranking_context: &RankingContext) {
+        let elo_diff = vrs_elo_result(teams[m.winner.0].elo, teams[m.loser().0].elo, m.information_context);
+
+        teams[m.winner.0].elo += elo_diff;
+        teams[m.loser().0].elo -= elo_diff;
+    }
+
+    fn predict(&self, team: &Team, opponent: &Team, _ranking_context: &RankingContext) -> f64 {
+        vrs_win_probability(team.elo, opponent.elo)
+    }
+}
@@ -1,30 +1,77 @@
 #![allow(dead_code)]
 
-use std::ops::RangeInclusive;
 use crate::data_loader::*;
-use crate::ranking_context;
+use crate::elo_ledger::*;
+use crate::error::*;
+use crate::registry::*;
 use crate::ranking_context::*;
+use crate::rating_system::require_alternative_vrs;
 use crate::util::*;
 
-pub fn gen_rank_new(matches: &[Match], events: &[Event], teams: &mut [Team], ranking_context: &RankingContext) {
-    seed_teams(matches, events, teams, ranking_context);
-    map_elo_adjustments(matches, teams, ranking_context);
+/// Runs the full ranking: seeds every team from the four factors, then adjusts the seeds map by map with Elo.
+/// The results are written to the teams, see Team::elo and Team::seed_points.
+pub fn gen_rank_new(matches: &[Match], events: &[Event], teams: &mut [Team], ranking_context: &RankingContext) -> Result<()> {
+    with_teams_in_id_order(teams, matches, |teams| {
+        seed_teams(matches, events, teams, ranking_context)?;
+        replay_maps(matches, teams, ranking_context, None);
+        Ok(())
+    })
 }
 
+/// Same as gen_rank_new, and also returns every rating change the Elo adjustment made. Refuses any rating system but
+/// the Alternative VRS, since that's the only one the ledger describes
+pub fn gen_rank_with_ledger(matches: &[Match], events: &[Event], teams: &mut [Team], ranking_context: &RankingContext) -> Result<EloLedger> {
+    require_alternative_vrs(ranking_context, "the ledger")?;
+
+    with_teams_in_id_order(teams, matches, |teams| {
+        seed_teams(matches, events, teams, ranking_context)?;
+
+        let mut ledger = EloLedger::default();
+        replay_maps(matches, teams, ranking_context, Some(&mut ledger));
+        Ok(ledger)
+    })
+}
+
+/// Elo gained by the winner (and lost by the loser) of a single map
 pub fn elo_result(winner_elo: f64, loser_elo: f64, ranking_context: &RankingContext) -> f64 {
     ranking_context.elo_k * (1.0 - 1.0 / ( 1.0 + f64::powf(10.0, (loser_elo - winner_elo)/ranking_context.elo_delta)))
 }
 
-fn seed_teams(matches: &[Match], events: &[Event], teams: &mut [Team], ranking_context: &RankingContext) {    
+/// The chance a team with elo has of beating a team with opponent_elo, as the Elo model sees it
+pub fn win_probability(elo: f64, opponent_elo: f64, ranking_context: &RankingContext) -> f64 {
+    1.0 / (1.0 + f64::powf(10.0, (opponent_elo - elo) / ranking_context.elo_delta))
+}
+
+/// What seed_teams scaled each factor by, and the range of summed factors it remapped to seed points.
+/// Only explain.rs needs these, the teams carry everything else.
+pub(crate) struct SeedReferences {
+    pub winnings: f64,
+    pub own_network: f64,
+    pub event_participation: f64,
+    pub opponent_winnings: f64,
+    pub opponent_network: f64,
+    pub lowest_sum_of_factors: f64,
+    pub highest_sum_of_factors: f64,
+}
+
+pub(crate) fn seed_teams(matches: &[Match], events: &[Event], teams: &mut [Team], ranking_context: &RankingContext) -> Result<SeedReferences> {
+    // These are added up below, so teams that have been ranked before start over
+    for team in teams.iter_mut() {
+        team.own_network = 0.0;
+        team.adjusted_winnings = 0.0;
+        team.event_participation = 0.0;
+    }
+
     // FaZe 1: Calculates Own Network
-    for (idx, team) in teams.iter_mut().enumerate() {
-        let mut opponents : Vec<(usize, f64)> = Vec::new();
+    for team in teams.iter_mut() {
+        let mut opponents : Vec<(TeamId, f64)> = Vec::new();
 
         for m in matches {
-            if m.winning_team != idx { continue; } // We only go through winning matches
+            if m.winner != team.id { continue; } // We only go through winning matches
 
-            // Adds information_context for each new opponent. If we've already played this team, update info context 
-            let opp_id = m.other_team(idx);
+            // Adds information_context for each new opponent. If we've already played this team, update info context.
+            // Matches are chronological, so we end up with the latest win against each opponent
+            let opp_id = m.other_team(team.id);
             let mut have_played_before = false;
             for op in &mut opponents {
                 if op.0 != opp_id { continue; }
@@ -46,23 +93,23 @@ fn seed_teams(matches: &[Match], events: &[Event], teams: &mut [Team], ranking_c
     // FaZe 2: Calculates Prize Winnings and Event Participation.
     for ev in events {
         for prize_dist in &ev.prize_distribution {
-            if !prize_dist.is_in_ranking { continue; }
+            let Some(team) = prize_dist.team else { continue; };
 
             let scale: f64 = ranking_context.time_mod(ev.last_match_time);
-            teams[prize_dist.team_id].adjusted_winnings += prize_dist.prize.sqrt() * scale;
-            teams[prize_dist.team_id].event_participation += ev.prize_pool.max(1.0).log10() * scale;
+            teams[team.0].adjusted_winnings += prize_dist.prize.sqrt() * scale;
+            teams[team.0].event_participation += ev.prize_pool.max(1.0).log10() * scale;
         }
     }    
 
     // Adjusts the three factors so that the nth highest score is 1.0
-    let reference_winnings = nth_highest(teams, ranking_context, |t| t.adjusted_winnings);
-    let reference_network  = nth_highest(teams, ranking_context, |t| t.own_network);
-    let reference_event    = nth_highest(teams, ranking_context, |t| t.event_participation);
+    let reference_winnings = nth_highest(teams, ranking_context, "adjusted winnings", |t| t.adjusted_winnings)?;
+    let reference_network  = nth_highest(teams, ranking_context, "own network", |t| t.own_network)?;
+    let reference_event    = nth_highest(teams, ranking_context, "event participation", |t| t.event_participation)?;
 
     for t in teams.iter_mut() {
-        t.prize_money           = f64::min(t.adjusted_winnings / reference_winnings, 1.0);
-        t.own_network           = f64::min( t.own_network / reference_network,1.0);
-        t.event_participation   = f64::min( t.event_participation / reference_event,1.0);
+        t.prize_money           = scale_to_reference(t.adjusted_winnings, reference_winnings);
+        t.own_network           = scale_to_reference(t.own_network, reference_network);
+        t.event_participation   = scale_to_reference(t.event_participation, reference_event);
     }
 
     // FaZe 3: Calculates Opponent Winnings and Opponent Network
@@ -71,25 +118,25 @@ fn seed_teams(matches: &[Match], events: &[Event], teams: &mut [Team], ranking_c
         let mut opp_networks = Vec::new();
 
         for m in matches {
-            if m.winning_team != idx { continue; }
+            if m.winner != TeamId(idx) { continue; }
 
-            let opp_idx = m.other_team(idx);
-            opp_winnings.push(teams[opp_idx].adjusted_winnings * m.information_context);
-            opp_networks.push(teams[opp_idx].own_network       * m.information_context);   
+            let opp_id = m.other_team(TeamId(idx));
+            opp_winnings.push(teams[opp_id.0].adjusted_winnings * m.information_context);
+            opp_networks.push(teams[opp_id.0].own_network       * m.information_context);   
         }
 
-        teams[idx].opponent_winnings = sum_of_nth_best(opp_winnings, ranking_context);
-        teams[idx].opponent_network  = sum_of_nth_best(opp_networks, ranking_context);
+        teams[idx].opponent_winnings = sum_of_nth_best(opp_winnings, ranking_context, "opponent winnings")?;
+        teams[idx].opponent_network  = sum_of_nth_best(opp_networks, ranking_context, "opponent network")?;
     }
 
-    let reference_opp_network  = nth_highest(teams, ranking_context, |t| t.opponent_network);
-    let reference_opp_winnings = nth_highest(teams, ranking_context, |t| t.opponent_winnings);
+    let reference_opp_network  = nth_highest(teams, ranking_context, "opponent network", |t| t.opponent_network)?;
+    let reference_opp_winnings = nth_highest(teams, ranking_context, "opponent winnings", |t| t.opponent_winnings)?;
 
     let mut highest_sum_of_factors = f64::MIN;
     let mut lowest_sum_of_factors  = f64::MAX;
-    for (idx, t) in teams.iter_mut().enumerate() {
-        t.opponent_winnings = curve_function(f64::min(t.opponent_winnings / reference_opp_winnings, 1.0));
-        t.opponent_network      = curve_function(f64::min(t.opponent_network / reference_opp_network, 1.0));
+    for t in teams.iter_mut() {
+        t.opponent_winnings = curve_function(scale_to_reference(t.opponent_winnings, reference_opp_winnings));
+        t.opponent_network  = curve_function(scale_to_reference(t.opponent_network, reference_opp_network));
 
         t.sum_of_factors = t.opponent_winnings   * ranking_context.opponet_winnings_weight
                          + t.prize_money         * ranking_context.prize_money_weight
@@ -110,24 +157,71 @@ fn seed_teams(matches: &[Match], events: &[Event], teams: &mut [Team], ranking_c
 
         team.elo = team.seed_points;
     }
+
+    Ok(SeedReferences {
+        winnings: reference_winnings,
+        own_network: reference_network,
+        event_participation: reference_event,
+        opponent_winnings: reference_opp_winnings,
+        opponent_network: reference_opp_network,
+        lowest_sum_of_factors,
+        highest_sum_of_factors,
+    })
 }
 
-fn map_elo_adjustments(matches: &[Match], teams: &mut [Team], ranking_context: &RankingContext) {
-    for m in matches {
-        for map in &m.maps {
-            let map_winning_team = if map.team_1_score > map.team_2_score { m.team_1_id } else { m.team_2_id };
-            let map_losing_team  = m.other_team(map_winning_team);
+/// The Head-to-Head adjustment. Replays history in order: matches chronologically, oldest first, and the maps inside
+/// each match in the order they're listed. Matches that start at the same time keep the order they were passed in.
+/// The order matters, since each map is rated with the Elo both teams had after every earlier map.
+/// The teams can be in any order, but every team the matches refer to has to be there.
+pub fn map_elo_adjustments(matches: &[Match], teams: &mut [Team], ranking_context: &RankingContext) -> Result<()> {
+    with_teams_in_id_order(teams, matches, |teams| {
+        replay_maps(matches, teams, ranking_context, None);
+        Ok(())
+    })
+}
+
+/// Same as map_elo_adjustments, and also returns every rating change it made
+pub fn map_elo_adjustments_with_ledger(matches: &[Match], teams: &mut [Team], ranking_context: &RankingContext) -> Result<EloLedger> {
+    with_teams_in_id_order(teams, matches, |teams| {
+        let mut ledger = EloLedger::default();
+        replay_maps(matches, teams, ranking_context, Some(&mut ledger));
+        Ok(ledger)
+    })
+}
+
+fn replay_maps(matches: &[Match], teams: &mut [Team], ranking_context: &RankingContext, mut ledger: Option<&mut EloLedger>) {
+    let mut chronological: Vec<&Match> = matches.iter().collect();
+    chronological.sort_by_key(|m| m.match_start_time);
 
-            let elo_diff = elo_result(teams[map_winning_team].elo, teams[map_losing_team].elo, ranking_context);
+    for m in chronological {
+        for map in &m.maps {
+            let (team_1_elo, team_2_elo) = (teams[m.team_1.0].elo, teams[m.team_2.0].elo);
+            let delta = apply_map_elo(m, map, teams, ranking_context, 1.0);
 
-            teams[map_winning_team].elo += elo_diff;
-            teams[map_losing_team].elo -= elo_diff;
+            if let Some(ledger) = ledger.as_deref_mut() {
+                let probability = win_probability(team_1_elo, team_2_elo, ranking_context);
+                ledger.entries.push(LedgerEntry::new(m, map, team_1_elo, team_2_elo, probability, delta));
+            }
         }
-    }        
+    }
 }
 
-// Expects an input between 0.0 and 1.0 inclusive. Curves the results out, simply meaning worse results become less worse
-// 0.1 => 0.5 | 0.2 => 0.6 | 0.5 => 0.75 | 0.8 => 0.9
+/// The Elo update for a single map, scaled by scale, which every map by map Elo system goes through. A tied map
+/// counts as a win for team 2. Returns how much team 1's Elo moved.
+pub(crate) fn apply_map_elo(m: &Match, map: &Map, teams: &mut [Team], ranking_context: &RankingContext, scale: f64) -> f64 {
+    let map_winning_team = if map.team_1_score > map.team_2_score { m.team_1 } else { m.team_2 };
+    let map_losing_team  = m.other_team(map_winning_team);
+
+    let elo_diff = elo_result(teams[map_winning_team.0].elo, teams[map_losing_team.0].elo, ranking_context) * scale;
+
+    teams[map_winning_team.0].elo += elo_diff;
+    teams[map_losing_team.0].elo -= elo_diff;
+
+    if map_winning_team == m.team_1 { elo_diff } else { -elo_diff }
+}
+
+/// Expects an input between 0.0 and 1.0 inclusive. Curves the results out, simply meaning worse results become less worse
+/// 0.1 => 0.5 | 0.2 => 0.6 | 0.5 => 0.75 | 0.8 => 0.9
 pub fn curve_function(x: f64) -> f64 {
     debug_assert!(x <= 1.0);
     debug_assert!(x >= 0.0);
@@ -135,20 +229,43 @@ pub fn curve_function(x: f64) -> f64 {
 }
 
 // Finds the Nth highest (from ranking_context.top_outlier_count) value of t_var for each team.
-// The closure is just a fancy way of letting us use this function for each factor.
-fn nth_highest<F>(teams: &[Team], ranking_context: &RankingContext, t_var: F) -> f64 where 
+// The closure is just a fancy way of letting us use this function for each factor. factor is only used for error messages.
+pub(crate) fn nth_highest<F>(teams: &[Team], ranking_context: &RankingContext, factor: &str, t_var: F) -> Result<f64> where 
     F: Fn(&Team) -> f64 {
     let mut var_vec = Vec::new();
     for t in teams {
-        var_vec.push(t_var(t));
+        let value = t_var(t);
+        if value.is_nan() {
+            return Err(Error::Numeric(format!("{factor} is NaN for {}", t.name)));
+        }
+        var_vec.push(value);
+    }
+
+    if var_vec.len() < ranking_context.top_outlier_count {
+        return Err(Error::Numeric(format!(
+            "can't scale {factor} by the {}th best team when there are only {} teams",
+            ranking_context.top_outlier_count,
+            var_vec.len(),
+        )));
     }
 
-    var_vec.sort_unstable_by(|a, b| b.partial_cmp(a).unwrap());
-    var_vec[ranking_context.top_outlier_count - 1]
+    var_vec.sort_unstable_by(|a, b| b.total_cmp(a));
+    Ok(var_vec[ranking_context.top_outlier_count - 1])
 }
 
-fn sum_of_nth_best(mut vec: Vec<f64>,ranking_context: &RankingContext) -> f64 {
-    vec.sort_by(|a, b| b.partial_cmp(a).unwrap());
+fn sum_of_nth_best(mut vec: Vec<f64>, ranking_context: &RankingContext, factor: &str) -> Result<f64> {
+    if vec.iter().any(|v| v.is_nan()) {
+        return Err(Error::Numeric(format!("{factor} of an opponent is NaN")));
+    }
+
+    vec.sort_by(|a, b| b.total_cmp(a));
     vec.resize(ranking_context.factor_bucket_size,0.0);
-    sum_vector(vec)
+    Ok(sum_vector(vec))
+}
+
+// Scales a factor so the reference team scores 1.0, capped at 1.0. If the reference team scored nothing,
+// nobody below it did either, so the factor can't tell anyone apart and everyone gets 0.0 rather than 0/0.
+pub(crate) fn scale_to_reference(value: f64, reference: f64) -> f64 {
+    if reference <= 0.0 { return 0.0; }
+    f64::min(value / reference, 1.0)
 }
\ No newline at end of file
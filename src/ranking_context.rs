@@ -1,6 +1,16 @@
 #![allow(dead_code)]
 
-#[derive(Debug)]
+use serde::*;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use crate::core_matching::CoreMatching;
+use crate::rating_system::RatingSystemKind;
+use crate::regions::RegionRule;
+
+/// Every field has a default, so a config file only needs to list the fields it changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct RankingContext {
     pub top_outlier_count: usize,
     pub factor_bucket_size: usize,
@@ -15,6 +25,7 @@ pub struct RankingContext {
 
     pub max_prize_pool_mod: f64,
 
+    #[serde(alias = "opponent_winnings_weight")]
     pub opponet_winnings_weight: f64,
     pub prize_money_weight: f64,
     pub opponent_network_weight: f64,
@@ -25,10 +36,21 @@ pub struct RankingContext {
 
     pub min_matches_for_ranking: u32,
     pub min_wins_for_ranking: u32,
+    // With a rating system that keeps a rating deviation, e.g. Glicko-2, this replaces min_matches_for_ranking
+    pub max_deviation_for_ranking: Option<f64>,
+
+    // Which lineups count as the same team. See core_matching.rs
+    pub core_matching: CoreMatching,
+
+    // How teams are assigned to regions from their core's nationalities. See regions.rs
+    pub region_rule: RegionRule,
+
+    // What rates the teams once they're loaded. See rating_system.rs
+    pub rating_system: RatingSystemKind,
 }
 
-impl RankingContext {
-    pub fn default() -> Self {
+impl Default for RankingContext {
+    fn default() -> Self {
         Self {
             top_outlier_count: 5,
             factor_bucket_size: 10,
@@ -53,13 +75,175 @@ impl RankingContext {
 
             min_matches_for_ranking: 10,
             min_wins_for_ranking: 1,
+            max_deviation_for_ranking: None,
+
+            core_matching: CoreMatching::default(),
+
+            region_rule: RegionRule::default(),
+
+            rating_system: RatingSystemKind::default(),
+        }
+    }
+}
+
+impl RankingContext {
+    /// Loads a context from a TOML or JSON file (decided by the file extension) on top of the defaults, and validates it.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ContextError> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path).map_err(|e| ContextError::Io(path.display().to_string(), e))?;
+
+        let is_toml = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+        let ranking_context: Self = if is_toml {
+            toml::from_str(&data).map_err(|e| ContextError::Parse(path.display().to_string(), e.to_string()))?
+        } else {
+            serde_json::from_str(&data).map_err(|e| ContextError::Parse(path.display().to_string(), e.to_string()))?
+        };
+
+        ranking_context.validate()?;
+        Ok(ranking_context)
+    }
+
+    /// Catches the settings that would otherwise panic or quietly produce nonsense further down the line
+    pub fn validate(&self) -> Result<(), ContextError> {
+        // nth_highest indexes with top_outlier_count - 1
+        if self.top_outlier_count == 0 {
+            return Err(ContextError::ZeroTopOutlierCount);
+        }
+
+        // time_mod subtracts the grace period from the end of the window, and then the start from that. If nothing is
+        // left it divides zero by zero
+        if (self.time_window_end as u64) <= self.time_window_start as u64 + self.time_grace_period as u64 {
+            return Err(ContextError::WindowShorterThanGracePeriod {
+                start: self.time_window_start,
+                end: self.time_window_end,
+                grace_period: self.time_grace_period,
+            });
+        }
+
+        let weights = [
+            ("opponet_winnings_weight",    self.opponet_winnings_weight),
+            ("prize_money_weight",         self.prize_money_weight),
+            ("opponent_network_weight",    self.opponent_network_weight),
+            ("event_participation_weight", self.event_participation_weight),
+        ];
+
+        for (field, value) in weights {
+            if value < 0.0 || value.is_nan() {
+                return Err(ContextError::NegativeWeight { field, value });
+            }
+        }
+
+        // A lineup has five players, so anything above that can never match and every lineup becomes a new team
+        let (min_overlap, last_n) = match self.core_matching {
+            CoreMatching::FirstSeen { min_overlap } | CoreMatching::Rolling { min_overlap } => (min_overlap as f64, 1),
+            CoreMatching::Weighted { last_n, min_overlap } => (min_overlap, last_n),
+        };
+        if !(min_overlap > 0.0 && min_overlap <= 5.0) || last_n == 0 {
+            return Err(ContextError::InvalidCoreMatching(self.core_matching));
         }
+
+        // Zero players would put teams without a single known nationality in a region, and a core only has five
+        if let RegionRule::AtLeast { players } = self.region_rule {
+            if !(1..=5).contains(&players) {
+                return Err(ContextError::InvalidRegionRule(self.region_rule));
+            }
+        }
+
+        // Glicko-2 divides by all of these
+        if let RatingSystemKind::Glicko2 { tau, volatility, max_deviation, min_deviation } = self.rating_system {
+            if !(tau > 0.0 && volatility > 0.0 && min_deviation > 0.0 && min_deviation <= max_deviation) {
+                return Err(ContextError::InvalidRatingSystem(self.rating_system));
+            }
+        }
+
+        // Zero or less leaves every team out of the standings, and NaN compares false against every deviation
+        if let Some(value) = self.max_deviation_for_ranking {
+            if !(value > 0.0 && value.is_finite()) {
+                return Err(ContextError::InvalidMaxDeviation(value));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets a numeric field by name, e.g. "elo_k". Integer fields are rounded to the nearest whole number.
+    /// Doesn't validate the result, since sweeps set several fields before it's worth checking.
+    pub fn set_field(&mut self, field: &str, value: f64) -> Result<(), ContextError> {
+        // Same alias the config files accept
+        let key = if field == "opponent_winnings_weight" { "opponet_winnings_weight" } else { field };
+
+        // Round trip through JSON, which keeps this in step with the fields without listing them all again
+        let mut json = serde_json::to_value(&*self).map_err(|e| ContextError::Parse(field.to_string(), e.to_string()))?;
+        let current = json.get_mut(key).filter(|v| v.is_number()).ok_or_else(|| ContextError::NotANumericField(field.to_string()))?;
+
+        let invalid = || ContextError::InvalidFieldValue { field: field.to_string(), value };
+        *current = if current.is_f64() {
+            serde_json::Number::from_f64(value).ok_or_else(invalid)?.into()
+        } else if value.is_finite() && value >= -0.5 {
+            (value.round() as u64).into()
+        } else {
+            return Err(invalid());
+        };
+
+        // Still fails if the rounded value doesn't fit, e.g. u32 fields
+        *self = serde_json::from_value(json).map_err(|_| invalid())?;
+        Ok(())
     }
 
     pub fn time_mod(&self, time: u32) -> f64 {
         let above = time.clamp(self.time_window_start, self.time_window_end - self.time_grace_period) - self.time_window_start;
         let below = self.time_window_end - self.time_window_start - self.time_grace_period;
 
-        ((above as f64) / (below as f64)).powf(self.time_decay_factor) 
+        ((above as f64) / (below as f64)).powf(self.time_decay_factor)
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug)]
+pub enum ContextError {
+    Io(String, std::io::Error),
+    Parse(String, String),
+    ZeroTopOutlierCount,
+    WindowShorterThanGracePeriod { start: u32, end: u32, grace_period: u32 },
+    NegativeWeight { field: &'static str, value: f64 },
+    InvalidCoreMatching(CoreMatching),
+    InvalidRegionRule(RegionRule),
+    InvalidRatingSystem(RatingSystemKind),
+    InvalidMaxDeviation(f64),
+    NotANumericField(String),
+    InvalidFieldValue { field: String, value: f64 },
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContextError::Io(path, e) => write!(f, "could not read config file {path}: {e}"),
+            ContextError::Parse(path, e) => write!(f, "could not parse config file {path}: {e}"),
+            ContextError::ZeroTopOutlierCount => write!(f, "top_outlier_count must be at least 1"),
+            ContextError::WindowShorterThanGracePeriod { start, end, grace_period } => write!(f,
+                "time_window_end ({end}) must be later than time_window_start ({start}) plus time_grace_period ({grace_period})"
+            ),
+            ContextError::NegativeWeight { field, value } => write!(f, "{field} must be zero or positive, got {value}"),
+            ContextError::InvalidCoreMatching(rule) => write!(f,
+                "core_matching ({rule}) needs a min_overlap above 0 and at most 5, and a last_n of at least 1"
+            ),
+            ContextError::InvalidRegionRule(rule) => write!(f, "region_rule ({rule}) needs between one and five players"),
+            ContextError::InvalidRatingSystem(system) => write!(f,
+                "rating_system ({system}) needs a positive tau and volatility, and a min_deviation above 0 and at most max_deviation"
+            ),
+            ContextError::InvalidMaxDeviation(value) => write!(f,
+                "max_deviation_for_ranking must be a positive, finite number, got {value}"
+            ),
+            ContextError::NotANumericField(field) => write!(f, "{field} isn't a number in the ranking context"),
+            ContextError::InvalidFieldValue { field, value } => write!(f, "{field} can't be set to {value}"),
+        }
+    }
+}
+
+impl std::error::Error for ContextError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ContextError::Io(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
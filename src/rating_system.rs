@@ -0,0 +1,280 @@
+use serde::*;
+use std::f64::consts::{LN_10, PI};
+use std::fmt;
+use crate::alt_rankings::{OrgRounds, PureElo, PureEloMap};
+use crate::data_loader::*;
+use crate::error::*;
+use crate::original_vrs::OriginalVrs;
+use crate::ranking::*;
+use crate::ranking_context::RankingContext;
+use crate::registry::{with_teams_in_id_order, TeamId};
+
+/// A way of rating teams from their matches. run_rating_system seeds every team, replays the matches oldest first,
+/// and finalizes. Ratings go in Team::elo, so every report works with any system, but predict decides what they mean.
+pub trait RatingSystem {
+    fn kind(&self) -> RatingSystemKind;
+
+    /// Gives every team its starting rating. The teams are in ID order
+    fn seed(&mut self, matches: &[Match], events: &[Event], teams: &mut [Team], ranking_context: &RankingContext) -> Result<()>;
+
+    /// Updates the ratings after a match. By default that's one update per map, in the order they're listed
+    fn update_match(&mut self, m: &Match, teams: &mut [Team], ranking_context: &RankingContext) {
+        for map in &m.maps {
+            self.update_map(m, map, teams, ranking_context);
+        }
+    }
+
+    /// Updates the ratings after a single map. Systems that rate whole matches override update_match instead
+    fn update_map(&mut self, _m: &Match, _map: &Map, _teams: &mut [Team], _ranking_context: &RankingContext) {}
+
+    /// The chance team has of beating opponent, going by their current ratings
+    fn predict(&self, team: &Team, opponent: &Team, ranking_context: &RankingContext) -> f64;
+
+    /// Runs once every match has been played
+    fn finalize(&mut self, _teams: &mut [Team], _ranking_context: &RankingContext) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The built-in rating systems. This is what goes in the RankingContext, and it's turned into a RatingSystem when ranking.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(tag = "system", rename_all = "snake_case")]
+pub enum RatingSystemKind {
+    /// The Alternative VRS: seeded from the four factors, then Elo map by map. Same as gen_rank_new
+    #[default]
+    AlternativeVrs,
+
+    /// Glicko-2 from the same seeds, with one rating period per map. Every team starts with a rating deviation of
+    /// max_deviation, which only its maps shrink, and never below min_deviation. tau limits how fast the volatility,
+    /// which starts at volatility, can change.
+    Glicko2 { tau: f64, volatility: f64, max_deviation: f64, min_deviation: f64 },
+
+    /// Plain Elo map by map from 1500, without seeding. See alt_rankings.rs
+    PureEloMap,
+
+    /// Plain Elo on whole matches from 1500, without seeding
+    PureElo,
+
+    /// Seeded like the Alternative VRS, with every map counting once for each round it was won by
+    OrgRounds,
+
+    /// Valve's original VRS, seeded from LAN Wins and the bounties, then its own Elo match by match. See original_vrs.rs
+    OriginalVrs,
+}
+
+impl RatingSystemKind {
+    pub fn system(&self) -> Box<dyn RatingSystem> {
+        match *self {
+            RatingSystemKind::AlternativeVrs => Box::new(AlternativeVrs),
+            RatingSystemKind::Glicko2 { tau, volatility, max_deviation, min_deviation } => {
+                Box::new(Glicko2::new(tau, volatility, max_deviation, min_deviation))
+            }
+            RatingSystemKind::PureEloMap => Box::new(PureEloMap),
+            RatingSystemKind::PureElo => Box::new(PureElo),
+            RatingSystemKind::OrgRounds => Box::new(OrgRounds),
+            RatingSystemKind::OriginalVrs => Box::new(OriginalVrs),
+        }
+    }
+}
+
+impl fmt::Display for RatingSystemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RatingSystemKind::AlternativeVrs => write!(f, "Alternative VRS"),
+            RatingSystemKind::Glicko2 { tau, volatility, max_deviation, min_deviation } => write!(f,
+                "Glicko-2, tau {tau}, volatility {volatility}, deviation {max_deviation} down to {min_deviation}"
+            ),
+            RatingSystemKind::PureEloMap => write!(f, "pure Elo per map"),
+            RatingSystemKind::PureElo => write!(f, "pure Elo per match"),
+            RatingSystemKind::OrgRounds => write!(f, "seeded Elo weighted by rounds"),
+            RatingSystemKind::OriginalVrs => write!(f, "Original VRS"),
+        }
+    }
+}
+
+/// For the breakdowns that follow the Alternative VRS step by step, which would quietly explain another model than
+/// the one that's configured
+pub(crate) fn require_alternative_vrs(ranking_context: &RankingContext, what: &'static str) -> Result<()> {
+    match ranking_context.rating_system {
+        RatingSystemKind::AlternativeVrs => Ok(()),
+        system => Err(Error::UnsupportedRatingSystem { what, system }),
+    }
+}
+
+/// Seeds, replays and finalizes. Matches that start at the same time keep the order they were passed in.
+pub fn run_rating_system(system: &mut dyn RatingSystem, matches: &[Match], events: &[Event], teams: &mut [Team], ranking_context: &RankingContext) -> Result<()> {
+    with_teams_in_id_order(teams, matches, |teams| {
+        system.seed(matches, events, teams, ranking_context)?;
+
+        let mut chronological: Vec<&Match> = matches.iter().collect();
+        chronological.sort_by_key(|m| m.match_start_time);
+        for m in chronological {
+            system.update_match(m, teams, ranking_context);
+        }
+
+        system.finalize(teams, ranking_context)
+    })
+}
+
+/// Ranks the teams with the rating system in ranking_context, and returns it for predictions
+pub fn rank(matches: &[Match], events: &[Event], teams: &mut [Team], ranking_context: &RankingContext) -> Result<Box<dyn RatingSystem>> {
+    let mut system = ranking_context.rating_system.system();
+    run_rating_system(system.as_mut(), matches, events, teams, ranking_context)?;
+    Ok(system)
+}
+
+/// gen_rank_new as a rating system
+pub struct AlternativeVrs;
+
+impl RatingSystem for AlternativeVrs {
+    fn kind(&self) -> RatingSystemKind {
+        RatingSystemKind::AlternativeVrs
+    }
+
+    fn seed(&mut self, matches: &[Match], events: &[Event], teams: &mut [Team], ranking_context: &RankingContext) -> Result<()> {
+        seed_teams(matches, events, teams, ranking_context)?;
+        Ok(())
+    }
+
+    // Same as map_elo_adjustments
+    fn update_map(&mut self, m: &Match, map: &Map, teams: &mut [Team], ranking_context: &RankingContext) {
+        apply_map_elo(m, map, teams, ranking_context, 1.0);
+    }
+
+    fn predict(&self, team: &Team, opponent: &Team, ranking_context: &RankingContext) -> f64 {
+        win_probability(team.elo, opponent.elo, ranking_context)
+    }
+}
+
+/// Glicko-2 as described by Mark Glickman, on the Elo scale of the seeds: elo_delta / ln(10) rating points to one
+/// Glicko-2 unit, which is the usual 173.7 for a delta of 400. Every map is a rating period of its own for the two
+/// teams in it. Deviations go in Team::rating_deviation, and the volatilities stay here.
+pub struct Glicko2 {
+    pub tau: f64,
+    pub volatility: f64,
+    pub max_deviation: f64,
+    pub min_deviation: f64,
+    // Indexed by TeamId
+    volatilities: Vec<f64>,
+}
+
+// When the volatility iteration stops
+const VOLATILITY_TOLERANCE: f64 = 1e-6;
+const MAX_VOLATILITY_ITERATIONS: usize = 100;
+
+impl Glicko2 {
+    pub fn new(tau: f64, volatility: f64, max_deviation: f64, min_deviation: f64) -> Self {
+        Self { tau, volatility, max_deviation, min_deviation, volatilities: Vec::new() }
+    }
+
+    pub fn volatility_of(&self, team: TeamId) -> f64 {
+        self.volatilities.get(team.0).copied().unwrap_or(self.volatility)
+    }
+
+    // Steps 3 to 7 of Glickman's paper for a single game, all in Glicko-2 units. Returns the new rating, deviation
+    // and volatility
+    fn rate(&self, (rating, deviation, volatility): (f64, f64, f64), (opponent, opponent_deviation): (f64, f64), score: f64) -> (f64, f64, f64) {
+        let g = g(opponent_deviation);
+        let expected = 1.0 / (1.0 + (-g * (rating - opponent)).exp());
+        let variance = 1.0 / (g * g * expected * (1.0 - expected));
+        let improvement = variance * g * (score - expected);
+
+        let volatility = self.new_volatility(deviation, volatility, variance, improvement);
+
+        let pre_period = (deviation * deviation + volatility * volatility).sqrt();
+        let deviation = 1.0 / (1.0 / (pre_period * pre_period) + 1.0 / variance).sqrt();
+        (rating + deviation * deviation * g * (score - expected), deviation, volatility)
+    }
+
+    // The Illinois algorithm from step 5
+    fn new_volatility(&self, deviation: f64, volatility: f64, variance: f64, improvement: f64) -> f64 {
+        let (phi2, delta2, tau2) = (deviation * deviation, improvement * improvement, self.tau * self.tau);
+        let a = (volatility * volatility).ln();
+        let f = |x: f64| {
+            let ex = x.exp();
+            ex * (delta2 - phi2 - variance - ex) / (2.0 * (phi2 + variance + ex).powi(2)) - (x - a) / tau2
+        };
+
+        let mut low = a;
+        let mut high = if delta2 > phi2 + variance {
+            (delta2 - phi2 - variance).ln()
+        } else {
+            let mut k = 1.0;
+            while f(a - k * self.tau) < 0.0 { k += 1.0; }
+            a - k * self.tau
+        };
+
+        let (mut f_low, mut f_high) = (f(low), f(high));
+        for _ in 0..MAX_VOLATILITY_ITERATIONS {
+            if (high - low).abs() <= VOLATILITY_TOLERANCE { break; }
+
+            let c = low + (low - high) * f_low / (f_high - f_low);
+            let f_c = f(c);
+            if f_c * f_high < 0.0 {
+                low = high;
+                f_low = f_high;
+            } else {
+                f_low /= 2.0;
+            }
+            high = c;
+            f_high = f_c;
+        }
+
+        (low / 2.0).exp()
+    }
+}
+
+// Shrinks a rating difference by how unsure we are of the opponent
+fn g(deviation: f64) -> f64 {
+    1.0 / (1.0 + 3.0 * deviation * deviation / (PI * PI)).sqrt()
+}
+
+fn glicko_scale(ranking_context: &RankingContext) -> f64 {
+    ranking_context.elo_delta / LN_10
+}
+
+impl RatingSystem for Glicko2 {
+    fn kind(&self) -> RatingSystemKind {
+        RatingSystemKind::Glicko2 { tau: self.tau, volatility: self.volatility, max_deviation: self.max_deviation, min_deviation: self.min_deviation }
+    }
+
+    fn seed(&mut self, matches: &[Match], events: &[Event], teams: &mut [Team], ranking_context: &RankingContext) -> Result<()> {
+        seed_teams(matches, events, teams, ranking_context)?;
+
+        // The seeds come from the same matches the replay goes through, so only the replay gets to shrink this
+        for t in teams.iter_mut() {
+            t.rating_deviation = Some(self.max_deviation);
+        }
+        self.volatilities = vec![self.volatility; teams.len()];
+        Ok(())
+    }
+
+    fn update_map(&mut self, m: &Match, map: &Map, teams: &mut [Team], ranking_context: &RankingContext) {
+        let scale = glicko_scale(ranking_context);
+        let state = |id: TeamId| {
+            let t = &teams[id.0];
+            (t.elo / scale, t.rating_deviation.unwrap_or(self.max_deviation) / scale, self.volatility_of(id))
+        };
+        let (team_1, team_2) = (state(m.team_1), state(m.team_2));
+
+        // Same as map_elo_adjustments, a tied map counts as a win for team 2
+        let score = if map.team_1_score > map.team_2_score { 1.0 } else { 0.0 };
+        let updated = [
+            (m.team_1, self.rate(team_1, (team_2.0, team_2.1), score)),
+            (m.team_2, self.rate(team_2, (team_1.0, team_1.1), 1.0 - score)),
+        ];
+
+        for (id, (rating, deviation, volatility)) in updated {
+            teams[id.0].elo = rating * scale;
+            teams[id.0].rating_deviation = Some((deviation * scale).max(self.min_deviation));
+            if let Some(v) = self.volatilities.get_mut(id.0) { *v = volatility; }
+        }
+    }
+
+    // Both deviations pull the prediction towards a coin flip. Without them this is win_probability
+    fn predict(&self, team: &Team, opponent: &Team, ranking_context: &RankingContext) -> f64 {
+        let scale = glicko_scale(ranking_context);
+        let deviation = (team.rating_deviation.unwrap_or(0.0).powi(2) + opponent.rating_deviation.unwrap_or(0.0).powi(2)).sqrt();
+        1.0 / (1.0 + (-g(deviation / scale) * (team.elo - opponent.elo) / scale).exp())
+    }
+}
@@ -0,0 +1,113 @@
+use serde::*;
+use std::fmt;
+use crate::data_loader::*;
+
+/// Valve's three regions. Europe takes in the CIS, the Middle East and Africa, and Asia takes in Oceania,
+/// the same way the RMRs are split.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Region {
+    Europe,
+    Americas,
+    Asia,
+}
+
+impl Region {
+    pub const ALL: [Region; 3] = [Region::Europe, Region::Americas, Region::Asia];
+
+    /// The region of a country, by the ISO 3166 code HLTV lists for each player. None for codes we don't know.
+    pub fn from_country_iso(country_iso: &str) -> Option<Region> {
+        let iso = country_iso.to_ascii_uppercase();
+
+        if AMERICAS.contains(&iso.as_str()) { return Some(Region::Americas); }
+        if ASIA.contains(&iso.as_str()) { return Some(Region::Asia); }
+        if EUROPE.contains(&iso.as_str()) { return Some(Region::Europe); }
+        None
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Region::Europe => write!(f, "Europe"),
+            Region::Americas => write!(f, "Americas"),
+            Region::Asia => write!(f, "Asia"),
+        }
+    }
+}
+
+/// How many of a team's core have to be from a region for the team to count as that region
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum RegionRule {
+    /// More than half of the core
+    #[default]
+    Majority,
+    /// Whichever region most of the core is from, as long as no other region has as many
+    Plurality,
+    /// At least this many players
+    AtLeast { players: usize },
+}
+
+impl fmt::Display for RegionRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegionRule::Majority => write!(f, "majority"),
+            RegionRule::Plurality => write!(f, "plurality"),
+            RegionRule::AtLeast { players } => write!(f, "at least {players} players"),
+        }
+    }
+}
+
+/// The region of a lineup under the rule, or None if the rule can't settle on one.
+/// Players from countries we don't know count towards the size of the lineup, but not towards any region.
+pub fn assign_region(players: &[Player], rule: RegionRule) -> Option<Region> {
+    let counts = Region::ALL.map(|region| {
+        (region, players.iter().filter(|p| Region::from_country_iso(&p.country_iso) == Some(region)).count())
+    });
+
+    let &(best, best_count) = counts.iter().max_by_key(|(_, count)| *count)?;
+    let tied = counts.iter().filter(|(_, count)| *count == best_count).count() > 1;
+
+    let assigned = match rule {
+        RegionRule::Majority => best_count * 2 > players.len(),
+        RegionRule::Plurality => best_count > 0 && !tied,
+        RegionRule::AtLeast { players } => best_count >= players && !tied,
+    };
+
+    if assigned { Some(best) } else { None }
+}
+
+/// Sets the region of every team from its core
+pub fn assign_regions(teams: &mut [Team], rule: RegionRule) {
+    for t in teams {
+        t.region = assign_region(&t.core, rule);
+    }
+}
+
+// Everything that isn't the Americas or Asia/Oceania. The CIS, the Middle East and Africa play in Europe.
+const EUROPE: &[&str] = &[
+    // Europe
+    "AD", "AL", "AT", "BA", "BE", "BG", "BY", "CH", "CY", "CZ", "DE", "DK", "EE", "ES", "FI", "FO", "FR", "GB", "GE",
+    "GI", "GR", "HR", "HU", "IE", "IS", "IT", "LI", "LT", "LU", "LV", "MC", "MD", "ME", "MK", "MT", "NL", "NO", "PL",
+    "PT", "RO", "RS", "RU", "SE", "SI", "SK", "SM", "TR", "UA", "VA", "XK", "AM", "AZ", "EU",
+    // CIS and Central Asia
+    "KZ", "KG", "TJ", "TM", "UZ",
+    // Middle East
+    "AE", "BH", "IL", "IQ", "IR", "JO", "KW", "LB", "OM", "PS", "QA", "SA", "SY", "YE",
+    // Africa
+    "DZ", "AO", "BW", "CM", "CI", "EG", "ET", "GH", "KE", "LY", "MA", "MU", "MZ", "NA", "NG", "RW", "SN", "TN", "TZ",
+    "UG", "ZA", "ZM", "ZW",
+];
+
+const AMERICAS: &[&str] = &[
+    "AR", "BO", "BR", "CA", "CL", "CO", "CR", "CU", "DO", "EC", "GT", "HN", "JM", "MX", "NI", "PA", "PE", "PR", "PY",
+    "SV", "TT", "US", "UY", "VE",
+];
+
+const ASIA: &[&str] = &[
+    // Asia
+    "AF", "BD", "BN", "BT", "CN", "HK", "ID", "IN", "JP", "KH", "KR", "LA", "LK", "MM", "MN", "MO", "MV", "MY", "NP",
+    "PH", "PK", "SG", "TH", "TW", "VN",
+    // Oceania
+    "AU", "FJ", "NZ", "PG",
+];
@@ -0,0 +1,153 @@
+use serde::*;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use crate::data_loader::*;
+use crate::error::*;
+
+/// A team as the ranking sees it, i.e. a core. It's the team's position in the team list load_data returns.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TeamId(pub usize);
+
+/// An event's position in the event list load_data returns
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EventId(pub usize);
+
+// Records are deserialized before load_data assigns them IDs. Anything that slips through unassigned
+// should blow up when it's used rather than quietly point at the first team.
+impl Default for TeamId {
+    fn default() -> Self { TeamId(usize::MAX) }
+}
+
+impl Default for EventId {
+    fn default() -> Self { EventId(usize::MAX) }
+}
+
+impl fmt::Display for TeamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.0) }
+}
+
+impl fmt::Display for EventId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.0) }
+}
+
+/// Maps HLTV IDs to our own IDs and back. An HLTV team ID can field several cores over the window (and a core can
+/// play under several HLTV IDs), so team lookups return every match.
+#[derive(Debug, Clone, Default)]
+pub struct Registry {
+    hltv_teams: HashMap<usize, Vec<TeamId>>,
+    hltv_events: HashMap<usize, EventId>,
+    team_hltv_ids: HashMap<TeamId, Vec<usize>>,
+    event_hltv_ids: HashMap<EventId, usize>,
+}
+
+impl Registry {
+    /// Works on sorted and filtered lists too, since every team and event carries both IDs
+    pub fn new(events: &[Event], teams: &[Team]) -> Self {
+        let mut registry = Self::default();
+
+        for t in teams {
+            for &hltv_id in &t.hltv_ids {
+                registry.hltv_teams.entry(hltv_id).or_default().push(t.id);
+            }
+            registry.team_hltv_ids.insert(t.id, t.hltv_ids.clone());
+        }
+
+        for ev in events {
+            registry.hltv_events.insert(ev.hltv_id, ev.id);
+            registry.event_hltv_ids.insert(ev.id, ev.hltv_id);
+        }
+
+        registry
+    }
+
+    /// Every core that played under this HLTV team ID, ordered by ID
+    pub fn teams(&self, hltv_team_id: usize) -> &[TeamId] {
+        self.hltv_teams.get(&hltv_team_id).map(|ids| &ids[..]).unwrap_or(&[])
+    }
+
+    /// Every HLTV team ID this core played under
+    pub fn hltv_team_ids(&self, team: TeamId) -> &[usize] {
+        self.team_hltv_ids.get(&team).map(|ids| &ids[..]).unwrap_or(&[])
+    }
+
+    pub fn event(&self, hltv_event_id: usize) -> Option<EventId> {
+        self.hltv_events.get(&hltv_event_id).copied()
+    }
+
+    pub fn hltv_event_id(&self, event: EventId) -> Option<usize> {
+        self.event_hltv_ids.get(&event).copied()
+    }
+}
+
+/// Looks teams up by TeamId in a list of any order, including one that's been filtered
+pub struct TeamIndex<'a> {
+    teams: Vec<Option<&'a Team>>,
+}
+
+impl<'a> TeamIndex<'a> {
+    pub fn new(teams: &'a [Team]) -> Self {
+        let len = teams.iter().map(|t| t.id.0 + 1).max().unwrap_or(0);
+        let mut index = vec![None; len];
+        for t in teams {
+            index[t.id.0] = Some(t);
+        }
+        Self { teams: index }
+    }
+
+    pub fn get(&self, team: TeamId) -> Option<&'a Team> {
+        self.teams.get(team.0).copied().flatten()
+    }
+
+    /// Both teams of a match, team 1 first. None if either of them isn't in the list
+    pub fn pair(&self, m: &Match) -> Option<(&'a Team, &'a Team)> {
+        Some((self.get(m.team_1)?, self.get(m.team_2)?))
+    }
+}
+
+/// The ranking indexes the team list by TeamId, so it needs every team in ID order. This runs rank on the teams in
+/// that order, on a copy if the list has been sorted, and puts every result back on the same team in the caller's list.
+/// A list that's missing any team the matches refer to is refused.
+pub(crate) fn with_teams_in_id_order<T>(teams: &mut [Team], matches: &[Match], rank: impl FnOnce(&mut [Team]) -> Result<T>) -> Result<T> {
+    if is_in_id_order(teams) {
+        check_complete(teams, matches)?;
+        return rank(teams);
+    }
+
+    let mut sorted = teams_by_id(teams, matches)?.into_owned();
+    let result = rank(&mut sorted)?;
+
+    for t in teams.iter_mut() {
+        let idx = t.id.0;
+        std::mem::swap(t, &mut sorted[idx]);
+    }
+    Ok(result)
+}
+
+/// The team list in ID order, for functions that rank a copy. Only copies the list if it's out of order.
+pub fn teams_by_id<'a>(teams: &'a [Team], matches: &[Match]) -> Result<Cow<'a, [Team]>> {
+    if is_in_id_order(teams) {
+        check_complete(teams, matches)?;
+        return Ok(Cow::Borrowed(teams));
+    }
+
+    let mut sorted = teams.to_vec();
+    sorted.sort_by_key(|t| t.id);
+    check_complete(&sorted, matches)?;
+    Ok(Cow::Owned(sorted))
+}
+
+fn is_in_id_order(teams: &[Team]) -> bool {
+    teams.iter().enumerate().all(|(idx, t)| t.id == TeamId(idx))
+}
+
+fn check_complete(teams: &[Team], matches: &[Match]) -> Result<()> {
+    if let Some((idx, _)) = teams.iter().enumerate().find(|(idx, t)| t.id != TeamId(*idx)) {
+        return Err(Error::MissingTeam(TeamId(idx)));
+    }
+
+    match matches.iter().flat_map(|m| [m.team_1, m.team_2]).find(|id| id.0 >= teams.len()) {
+        Some(id) => Err(Error::MissingTeam(id)),
+        None => Ok(()),
+    }
+}
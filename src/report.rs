@@ -1,14 +1,11 @@
 use crate::*;
+use crate::util::format_date;
 
-pub fn print_to_console(mut teams: Vec<Team>, ranking_context: &RankingContext) {
-    teams.sort_by(|a, b| b.elo.partial_cmp(&a.elo).unwrap());
-
-    let mut rank = 1;
-    for t in teams {
-        if !ranking_eligible(&t, &ranking_context) { continue; }
-
-        println!("{8:3}. {6:20} | Elo {0:6.1} | Diff {7:6.1} | Seed {1:6.1} | PM {2:.3} | OW {3:.3} | EP {4:.3} | ON {5:.3} | $EARNED {9:.0}",
-            t.elo,
+/// Prints every ranking eligible team sorted by Elo, with each factor
+pub fn print_to_console(teams: Vec<Team>, ranking_context: &RankingContext) {
+    for (rank, t) in ranked_teams(&teams, ranking_context) {
+        println!("{8:3}. {6:20} | Elo {0:>6} | Diff {7:6.1} | Seed {1:6.1} | PM {2:.3} | OW {3:.3} | EP {4:.3} | ON {5:.3} | $EARNED {9:.0}",
+            shown_rating(t),
             t.seed_points,
             t.prize_money,
             t.opponent_winnings,
@@ -19,29 +16,207 @@ pub fn print_to_console(mut teams: Vec<Team>, ranking_context: &RankingContext)
             rank,
             t.adjusted_winnings,
         );
-
-        rank += 1;
     }
 }
 
+/// Prints every ranking eligible team sorted by Elo as a Markdown table row, with its current players and latest roster changes
 pub fn output_report(teams: Vec<Team>, ranking_context: &RankingContext) {
-    let mut i = 1;
-    for t in teams {
-        if !ranking_eligible(&t, &ranking_context) { continue; }
-
-        let players = format!("{}, {}, {}, {}, {}",t.core[0].nick,t.core[1].nick,t.core[2].nick,t.core[3].nick,t.core[4].nick);
-
-        println!("| {0:3}. | {1:20} | {2:6.1} | {3:50} |",
-            i,
+    for (rank, t) in ranked_teams(&teams, ranking_context) {
+        println!("| {0:3}. | {1:20} | {2:>6} | {3:50} | {4} |",
+            rank,
             t.name,
-            t.elo,
-            players,
+            shown_rating(t),
+            current_players(t),
+            recent_changes(t, REPORTED_CHANGES),
         );
+    }
+}
+
+/// Prints the standings of each region, with both the regional and the global rank of each ranking eligible team.
+/// Teams the region rule couldn't place are listed at the end, so nobody disappears from the report.
+pub fn print_regional_standings(teams: Vec<Team>, ranking_context: &RankingContext) {
+    let ranked = ranked_teams(&teams, ranking_context);
+
+    let regions = Region::ALL.iter().map(|&r| (Some(r), r.to_string())).chain([(None, "No region".to_string())]);
+    for (region, title) in regions {
+        let standings: Vec<&(usize, &Team)> = ranked.iter().filter(|(_, t)| t.region == region).collect();
+        if standings.is_empty() { continue; }
+
+        println!("## {title} ({})", ranking_context.region_rule);
+        println!("| Region | Global | {0:20} | {1:6} |", "Team", "Elo");
+
+        // Teams tied globally are tied within the region too
+        let mut regional_rank = 0;
+        for (idx, &&(global_rank, t)) in standings.iter().enumerate() {
+            if idx == 0 || standings[idx - 1].0 != global_rank { regional_rank = idx + 1; }
+            println!("| {0:5}. | {1:5}. | {2:20} | {3:>6} |", regional_rank, global_rank, t.name, shown_rating(t));
+        }
+        println!();
+    }
+}
+
+/// The standings as a Markdown document: a header with the time window and the model settings, then a table with
+/// every factor and how far each team's Elo moved from its seed. Ready to paste into a readme.
+pub fn markdown_report(teams: &[Team], ranking_context: &RankingContext) -> String {
+    let mut report = String::new();
+
+    report += "# Alternative VRS standings\n\n";
+    report += &format!("Matches from {} to {}.\n\n", format_date(ranking_context.time_window_start), format_date(ranking_context.time_window_end));
+    for (setting, value) in model_settings(ranking_context) {
+        report += &format!("- {setting}: {value}\n");
+    }
+    report += "\n";
 
-        i += 1;
+    report += &format!("| {} |\n", REPORT_COLUMNS.join(" | "));
+    report += &format!("|{}\n", REPORT_COLUMNS.iter().enumerate().map(|(idx, _)| if idx == 1 || idx == 9 { " --- |" } else { " ---: |" }).collect::<String>());
+    for (rank, t) in ranked_teams(teams, ranking_context) {
+        let row = report_row(rank, t);
+        report += &format!("| {} |\n", row.iter().map(|cell| cell.replace('|', "\\|")).collect::<Vec<_>>().join(" | "));
     }
+
+    report
+}
+
+/// Same as markdown_report, as a standalone HTML page
+pub fn html_report(teams: &[Team], ranking_context: &RankingContext) -> String {
+    let mut report = String::new();
+
+    report += "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Alternative VRS standings</title>\n</head>\n<body>\n";
+    report += "<h1>Alternative VRS standings</h1>\n";
+    report += &format!("<p>Matches from {} to {}.</p>\n", format_date(ranking_context.time_window_start), format_date(ranking_context.time_window_end));
+    report += "<ul>\n";
+    for (setting, value) in model_settings(ranking_context) {
+        report += &format!("<li>{}: {}</li>\n", setting, escape_html(&value));
+    }
+    report += "</ul>\n";
+
+    report += "<table>\n<thead>\n<tr>";
+    for column in REPORT_COLUMNS {
+        report += &format!("<th>{column}</th>");
+    }
+    report += "</tr>\n</thead>\n<tbody>\n";
+    for (rank, t) in ranked_teams(teams, ranking_context) {
+        report += "<tr>";
+        for cell in report_row(rank, t) {
+            report += &format!("<td>{}</td>", escape_html(&cell));
+        }
+        report += "</tr>\n";
+    }
+    report += "</tbody>\n</table>\n</body>\n</html>\n";
+
+    report
+}
+
+/// Ranking eligible teams sorted by Elo, with their rank. Teams with the same Elo to one decimal, which is what the
+/// reports show, share a rank and the rank after them is skipped (1, 2, 2, 4). Tied teams are listed by name.
+pub fn ranked_teams<'a>(teams: &'a [Team], ranking_context: &RankingContext) -> Vec<(usize, &'a Team)> {
+    let shown_elo = |t: &Team| (t.elo * 10.0).round();
+
+    let mut sorted: Vec<&Team> = teams.iter().filter(|t| ranking_eligible(t, ranking_context)).collect();
+    sorted.sort_by(|a, b| shown_elo(b).total_cmp(&shown_elo(a)).then_with(|| a.name.cmp(&b.name)));
+
+    let mut ranked: Vec<(usize, &Team)> = Vec::with_capacity(sorted.len());
+    for (idx, t) in sorted.into_iter().enumerate() {
+        let rank = match ranked.last() {
+            Some(&(previous_rank, previous)) if shown_elo(previous) == shown_elo(t) => previous_rank,
+            _ => idx + 1,
+        };
+        ranked.push((rank, t));
+    }
+
+    ranked
+}
+
+const REPORTED_CHANGES: usize = 2;
+
+const REPORT_COLUMNS: [&str; 10] = [
+    "Rank", "Team", "Elo", "Seed", "Diff", "Prize Money", "Opponent Winnings", "Event Participation", "Opponent Network", "Players",
+];
+
+fn report_row(rank: usize, t: &Team) -> Vec<String> {
+    vec![
+        rank.to_string(),
+        t.name.clone(),
+        shown_rating(t),
+        format!("{:.1}", t.seed_points),
+        format!("{:+.1}", t.elo - t.seed_points),
+        format!("{:.3}", t.prize_money),
+        format!("{:.3}", t.opponent_winnings),
+        format!("{:.3}", t.event_participation),
+        format!("{:.3}", t.opponent_network),
+        current_players(t),
+    ]
+}
+
+// Elo to one decimal, with a 95% interval if the rating system keeps a deviation, e.g. "1650.2 ± 98.0"
+fn shown_rating(t: &Team) -> String {
+    match t.rating_deviation {
+        Some(deviation) => format!("{:.1} ± {:.1}", t.elo, RATING_INTERVAL_Z * deviation),
+        None => format!("{:.1}", t.elo),
+    }
+}
+
+const RATING_INTERVAL_Z: f64 = 1.96;
+
+// Everything in the ranking context that changes the standings, except the time window
+fn model_settings(ranking_context: &RankingContext) -> Vec<(&'static str, String)> {
+    vec![
+        ("Elo K", ranking_context.elo_k.to_string()),
+        ("Elo delta", ranking_context.elo_delta.to_string()),
+        ("Seeded rank", format!("{} to {}", ranking_context.min_seeded_rank, ranking_context.max_seeded_rank)),
+        ("Top outliers", ranking_context.top_outlier_count.to_string()),
+        ("Factor bucket size", ranking_context.factor_bucket_size.to_string()),
+        ("Grace period", format!("{} days", ranking_context.time_grace_period / (24 * 60 * 60))),
+        ("Time decay", ranking_context.time_decay_factor.to_string()),
+        ("Max prize pool", ranking_context.max_prize_pool_mod.to_string()),
+        ("Weights", format!("prize money {}, opponent winnings {}, event participation {}, opponent network {}",
+            ranking_context.prize_money_weight,
+            ranking_context.opponet_winnings_weight,
+            ranking_context.event_participation_weight,
+            ranking_context.opponent_network_weight,
+        )),
+        ("Eligible with", match ranking_context.max_deviation_for_ranking {
+            Some(deviation) => format!("a rating deviation of at most {deviation} (or {} matches without one) and {} wins",
+                ranking_context.min_matches_for_ranking, ranking_context.min_wins_for_ranking),
+            None => format!("{} matches and {} wins", ranking_context.min_matches_for_ranking, ranking_context.min_wins_for_ranking),
+        }),
+        ("Core matching", ranking_context.core_matching.to_string()),
+        ("Regions", ranking_context.region_rule.to_string()),
+        ("Rating system", ranking_context.rating_system.to_string()),
+    ]
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Teams that didn't come out of load_data don't have a history, but they do have a core
+fn current_players(team: &Team) -> String {
+    match team.roster.current() {
+        Some(lineup) => nicks(&lineup.players),
+        None => nicks(&team.core),
+    }
+}
+
+fn nicks(players: &[Player]) -> String {
+    players.iter().map(|p| p.nick.as_str()).collect::<Vec<_>>().join(", ")
+}
+
+// The latest few roster changes, newest first, e.g. "2023-08-01 +ropz -gla1ve"
+fn recent_changes(team: &Team, count: usize) -> String {
+    team.roster.changes().iter().rev().take(count).map(|c| {
+        let joined = c.joined.iter().map(|p| format!(" +{}", p.nick));
+        let left = c.left.iter().map(|p| format!(" -{}", p.nick));
+        format!("{}{}", format_date(c.time), joined.chain(left).collect::<String>())
+    }).collect::<Vec<_>>().join("; ")
 }
 
+/// Teams need a minimum number of matches and wins to show up in the standings. With max_deviation_for_ranking set,
+/// a team the rating system keeps a deviation for needs that deviation to be low enough instead of the matches.
 pub fn ranking_eligible(team: &Team, ranking_context: &RankingContext) -> bool {
-    team.matches_played >= ranking_context.min_matches_for_ranking && team.matches_won >= ranking_context.min_wins_for_ranking
+    let enough_data = match (ranking_context.max_deviation_for_ranking, team.rating_deviation) {
+        (Some(max_deviation), Some(deviation)) => deviation <= max_deviation,
+        _ => team.matches_played >= ranking_context.min_matches_for_ranking,
+    };
+    enough_data && team.matches_won >= ranking_context.min_wins_for_ranking
 }
\ No newline at end of file
@@ -0,0 +1,87 @@
+use serde::*;
+use crate::data_loader::*;
+
+/// One unbroken stretch of matches a team played with the same five (or however many HLTV listed) players
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Lineup {
+    pub players: Vec<Player>,
+    pub first_match: u32,
+    pub last_match: u32,
+    pub matches_played: u32,
+}
+
+impl Lineup {
+    pub fn has_player(&self, player_id: u16) -> bool {
+        self.players.iter().any(|p| p.player_id == player_id)
+    }
+
+    fn same_players(&self, players: &[Player]) -> bool {
+        self.players.len() == players.len() && players.iter().all(|p| self.has_player(p.player_id))
+    }
+}
+
+/// Who joined and left a team between two lineups. A stand-in shows up as a change when they come in, and another
+/// when the regular player returns.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RosterChange {
+    /// Start time of the first match with the new lineup
+    pub time: u32,
+    pub joined: Vec<Player>,
+    pub left: Vec<Player>,
+}
+
+/// Every lineup a team played with in the window, oldest first. Filled in by load_data, from the players
+/// listed for each match.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RosterHistory {
+    lineups: Vec<Lineup>,
+}
+
+impl RosterHistory {
+    /// Adds a match to the history. Matches have to be recorded oldest first.
+    pub fn record(&mut self, match_start_time: u32, players: &[Player]) {
+        debug_assert!(self.lineups.last().is_none_or(|l| l.last_match <= match_start_time));
+
+        if let Some(lineup) = self.lineups.last_mut() {
+            if lineup.same_players(players) {
+                lineup.last_match = match_start_time;
+                lineup.matches_played += 1;
+                return;
+            }
+        }
+
+        self.lineups.push(Lineup {
+            players: players.to_vec(),
+            first_match: match_start_time,
+            last_match: match_start_time,
+            matches_played: 1,
+        });
+    }
+
+    pub fn lineups(&self) -> &[Lineup] {
+        &self.lineups
+    }
+
+    /// The lineup the team played its last match with
+    pub fn current(&self) -> Option<&Lineup> {
+        self.lineups.last()
+    }
+
+    /// The lineup the team was playing with at that time, i.e. the last one that had played a match by then
+    pub fn lineup_at(&self, time: u32) -> Option<&Lineup> {
+        self.lineups.iter().rev().find(|l| l.first_match <= time)
+    }
+
+    /// Every change between consecutive lineups, oldest first
+    pub fn changes(&self) -> Vec<RosterChange> {
+        self.lineups.windows(2).map(|pair| {
+            let (before, after) = (&pair[0], &pair[1]);
+
+            RosterChange {
+                time: after.first_match,
+                joined: after.players.iter().filter(|p| !before.has_player(p.player_id)).cloned().collect(),
+                left: before.players.iter().filter(|p| !after.has_player(p.player_id)).cloned().collect(),
+            }
+        }).collect()
+    }
+}
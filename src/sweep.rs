@@ -0,0 +1,266 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::*;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use crate::data_loader::*;
+use crate::error::*;
+use crate::ranking_context::RankingContext;
+use crate::rating_system::rank;
+use crate::fit::analyze_fit;
+
+/// A ranking context field, e.g. elo_k, and the range to try it over. Grid sweeps step from from to to, both included.
+/// Random sweeps draw anywhere between the two and ignore step.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ParameterRange {
+    pub field: String,
+    pub from: f64,
+    pub to: f64,
+    pub step: f64,
+}
+
+impl ParameterRange {
+    pub fn grid_values(&self) -> Vec<f64> {
+        if self.step <= 0.0 || self.to <= self.from {
+            return vec![self.from];
+        }
+
+        // Allow for rounding, so 0:1:0.1 ends at 1 and not 0.9
+        let steps = ((self.to - self.from) / self.step + 1e-9).floor() as usize;
+        (0..=steps).map(|i| self.from + i as f64 * self.step).collect()
+    }
+}
+
+/// Parses field=from:to:step, e.g. elo_k=16:64:8. Without a step, the range is split in ten.
+impl FromStr for ParameterRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, String> {
+        let (field, range) = s.split_once('=').ok_or_else(|| format!("expected field=from:to:step, got {s}"))?;
+
+        let numbers = range.split(':')
+            .map(|n| n.trim().parse::<f64>().map_err(|e| format!("{n} in {s}: {e}")))
+            .collect::<std::result::Result<Vec<f64>, String>>()?;
+
+        let (from, to, step) = match numbers[..] {
+            [from, to, step] => (from, to, step),
+            [from, to] => (from, to, (to - from) / 10.0),
+            [value] => (value, value, 0.0),
+            _ => return Err(format!("expected field=from:to:step, got {s}")),
+        };
+        if from > to {
+            return Err(format!("{s} starts after it ends"));
+        }
+
+        Ok(Self { field: field.trim().to_string(), from, to, step })
+    }
+}
+
+/// Every combination of the grid, or a number of random ones. Random sweeps draw the same combinations for the same seed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum SweepMode {
+    Grid,
+    Random { samples: usize, seed: u64 },
+}
+
+/// Reruns the ranking for combinations of ranking context fields, and scores each with analyze_fit
+#[derive(Debug, Clone)]
+pub struct Sweep {
+    pub parameters: Vec<ParameterRange>,
+    pub mode: SweepMode,
+    /// Combinations run on this many threads at once. The results don't depend on it
+    pub threads: usize,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SweepResult {
+    /// In the same order as the parameters
+    pub values: Vec<f64>,
+    pub error: f64,
+}
+
+/// A combination that couldn't be ranked, e.g. because it made a weight negative or left too few teams to scale by
+#[derive(Serialize, Debug, Clone)]
+pub struct SweepFailure {
+    pub values: Vec<f64>,
+    pub reason: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SweepResults {
+    pub fields: Vec<String>,
+    pub mode: SweepMode,
+    /// What every field that isn't swept was set to
+    pub ranking_context: RankingContext,
+    /// Lowest error first
+    pub results: Vec<SweepResult>,
+    pub failed: Vec<SweepFailure>,
+}
+
+impl Sweep {
+    pub fn grid(parameters: Vec<ParameterRange>) -> Self {
+        Self { parameters, mode: SweepMode::Grid, threads: default_threads() }
+    }
+
+    pub fn random(parameters: Vec<ParameterRange>, samples: usize, seed: u64) -> Self {
+        Self { parameters, mode: SweepMode::Random { samples, seed }, threads: default_threads() }
+    }
+
+    /// Every combination the sweep will run, in the order they're reported in before sorting
+    pub fn combinations(&self) -> Vec<Vec<f64>> {
+        match self.mode {
+            SweepMode::Grid => {
+                let mut combinations = vec![Vec::new()];
+                for p in &self.parameters {
+                    let values = p.grid_values();
+                    combinations = combinations.iter()
+                        .flat_map(|c| values.iter().map(move |&v| {
+                            let mut c = c.clone();
+                            c.push(v);
+                            c
+                        }))
+                        .collect();
+                }
+                combinations
+            }
+            SweepMode::Random { samples, seed } => {
+                let mut rng = StdRng::seed_from_u64(seed);
+                (0..samples)
+                    .map(|_| self.parameters.iter().map(|p| {
+                        if p.to > p.from { rng.random_range(p.from..=p.to) } else { p.from }
+                    }).collect())
+                    .collect()
+            }
+        }
+    }
+
+    /// Runs every combination on top of ranking_context. Fails straight away on fields that can't be swept, but a
+    /// combination that can't be ranked only ends up in failed.
+    pub fn run(&self, dataset: &Dataset, ranking_context: &RankingContext) -> Result<SweepResults> {
+        let mut check = ranking_context.clone();
+        for p in &self.parameters {
+            check.set_field(&p.field, p.from)?;
+        }
+
+        let combinations = self.combinations();
+        let next = AtomicUsize::new(0);
+        let outcomes = Mutex::new(Vec::with_capacity(combinations.len()));
+
+        // Each thread takes the next combination until there are none left. The dataset is only ever read
+        thread::scope(|scope| {
+            for _ in 0..self.threads.clamp(1, combinations.len().max(1)) {
+                scope.spawn(|| loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(values) = combinations.get(i) else { break };
+
+                    let outcome = self.evaluate(dataset, ranking_context, values);
+                    outcomes.lock().unwrap_or_else(|e| e.into_inner()).push((i, outcome));
+                });
+            }
+        });
+
+        // Back in combination order, so the sort below breaks ties the same way every time
+        let mut outcomes = outcomes.into_inner().unwrap_or_else(|e| e.into_inner());
+        outcomes.sort_by_key(|(i, _)| *i);
+
+        let mut results = Vec::new();
+        let mut failed = Vec::new();
+        for (i, outcome) in outcomes {
+            let values = combinations[i].clone();
+            match outcome {
+                Ok(error) => results.push(SweepResult { values, error }),
+                Err(e) => failed.push(SweepFailure { values, reason: e.to_string() }),
+            }
+        }
+        results.sort_by(|a, b| a.error.total_cmp(&b.error));
+
+        Ok(SweepResults {
+            fields: self.parameters.iter().map(|p| p.field.clone()).collect(),
+            mode: self.mode,
+            ranking_context: ranking_context.clone(),
+            results,
+            failed,
+        })
+    }
+
+    fn evaluate(&self, dataset: &Dataset, ranking_context: &RankingContext, values: &[f64]) -> Result<f64> {
+        let mut ranking_context = ranking_context.clone();
+        for (p, &value) in self.parameters.iter().zip(values) {
+            ranking_context.set_field(&p.field, value)?;
+        }
+
+        let (matches, events, mut teams) = dataset.window(&ranking_context)?;
+        rank(&matches, &events, &mut teams, &ranking_context)?;
+        analyze_fit(&teams, &matches, &ranking_context, false)
+    }
+}
+
+fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+impl SweepResults {
+    pub fn best(&self) -> Option<&SweepResult> {
+        self.results.first()
+    }
+
+    pub fn write_json<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writeln!(writer)?;
+        writer.flush()
+    }
+
+    /// One row per combination, best first. Failed combinations come last, with an empty error
+    pub fn write_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut csv_writer = csv::Writer::from_writer(writer);
+
+        let mut header = vec!["rank".to_string()];
+        header.extend(self.fields.iter().cloned());
+        header.extend(["error".to_string(), "failure".to_string()]);
+        csv_writer.write_record(&header)?;
+
+        for (i, r) in self.results.iter().enumerate() {
+            let mut record = vec![(i + 1).to_string()];
+            record.extend(r.values.iter().map(|v| v.to_string()));
+            record.extend([r.error.to_string(), String::new()]);
+            csv_writer.write_record(&record)?;
+        }
+        for f in &self.failed {
+            let mut record = vec![String::new()];
+            record.extend(f.values.iter().map(|v| v.to_string()));
+            record.extend([String::new(), f.reason.clone()]);
+            csv_writer.write_record(&record)?;
+        }
+
+        csv_writer.flush()
+    }
+}
+
+impl fmt::Display for SweepResults {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Rank |")?;
+        for field in &self.fields {
+            write!(f, " {field:>12} |")?;
+        }
+        writeln!(f, " Error")?;
+
+        for (i, r) in self.results.iter().enumerate() {
+            write!(f, "{:4} |", i + 1)?;
+            for (field, value) in self.fields.iter().zip(&r.values) {
+                write!(f, " {value:>width$.4} |", width = field.len().max(12))?;
+            }
+            writeln!(f, " {:.6}", r.error)?;
+        }
+
+        for failure in &self.failed {
+            let values: Vec<String> = self.fields.iter().zip(&failure.values).map(|(field, v)| format!("{field} {v:.4}")).collect();
+            writeln!(f, "Failed with {}: {}", values.join(", "), failure.reason)?;
+        }
+        Ok(())
+    }
+}
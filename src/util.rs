@@ -13,4 +13,61 @@ pub fn remap_value_clamped(val: f64, in_low: f64, in_high: f64, out_low: f64, ou
     let clamped = interpolated.clamp(0.0, 1.0);
 
     clamped * out_high + ( 1.0 - clamped ) * out_low
-}
\ No newline at end of file
+}
+
+/// Parses either a unix timestamp or a YYYY-MM-DD date (midnight UTC) into a unix timestamp
+pub fn parse_date(input: &str) -> Option<u32> {
+    if let Ok(timestamp) = input.parse::<u32>() {
+        return Some(timestamp);
+    }
+
+    let mut parts = input.split('-');
+    let year:  i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day:   i64 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) { return None; }
+
+    // Days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+
+    u32::try_from(days * 24 * 60 * 60).ok()
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Like parse_date, but a YYYY-MM-DD date is the last second of that day, so a window ending on a date includes it
+pub fn parse_end_date(input: &str) -> Option<u32> {
+    if let Ok(timestamp) = input.parse::<u32>() {
+        return Some(timestamp);
+    }
+
+    parse_date(input)?.checked_add(24 * 60 * 60 - 1)
+}
+
+/// Formats a unix timestamp as a YYYY-MM-DD date (UTC)
+pub fn format_date(timestamp: u32) -> String {
+    // The inverse of parse_date, see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = (timestamp / (24 * 60 * 60)) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
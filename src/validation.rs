@@ -0,0 +1,322 @@
+use serde::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use crate::data_loader::*;
+use crate::error::*;
+use crate::ranking_context::RankingContext;
+
+/// How load_data treats a record with an issue
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// load_data refuses to load the feed until it's fixed. Outside the time window these are only dropped
+    Error,
+    /// The record is skipped
+    Dropped,
+    /// The record is used, but probably isn't what the feed meant
+    Warning,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Issue {
+    IncompleteLineup { team_1_players: usize, team_2_players: usize },
+    OutsideWindow { window_start: u32, window_end: u32 },
+    Showmatch { event_name: String },
+    UnknownEvent,
+    InvalidWinningTeam { winning_team: usize },
+    DuplicateMatch { first_index: usize },
+    NoMaps,
+    TiedMap { map_name: String, team_1_score: u16, team_2_score: u16 },
+    WinnerDisagreesWithMaps { winning_team: usize, team_1_maps: usize, team_2_maps: usize },
+    DuplicateEvent,
+    UnknownPrizeTeam { team_id: usize, placement: u32 },
+}
+
+impl Issue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Issue::UnknownEvent => Severity::Error,
+
+            Issue::IncompleteLineup { .. }
+            | Issue::OutsideWindow { .. }
+            | Issue::Showmatch { .. }
+            | Issue::InvalidWinningTeam { .. }
+            | Issue::DuplicateMatch { .. }
+            | Issue::DuplicateEvent => Severity::Dropped,
+
+            // The placement is skipped, the rest of the event is kept
+            Issue::NoMaps | Issue::TiedMap { .. } | Issue::WinnerDisagreesWithMaps { .. } | Issue::UnknownPrizeTeam { .. } => Severity::Warning,
+        }
+    }
+
+    // Used to group issues in the summary
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Issue::IncompleteLineup { .. }        => "incomplete_lineup",
+            Issue::OutsideWindow { .. }           => "outside_window",
+            Issue::Showmatch { .. }               => "showmatch",
+            Issue::UnknownEvent                   => "unknown_event",
+            Issue::InvalidWinningTeam { .. }      => "invalid_winning_team",
+            Issue::DuplicateMatch { .. }          => "duplicate_match",
+            Issue::NoMaps                         => "no_maps",
+            Issue::TiedMap { .. }                 => "tied_map",
+            Issue::WinnerDisagreesWithMaps { .. } => "winner_disagrees_with_maps",
+            Issue::DuplicateEvent                 => "duplicate_event",
+            Issue::UnknownPrizeTeam { .. }        => "unknown_prize_team",
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::IncompleteLineup { team_1_players, team_2_players } => write!(f, "lineups have {team_1_players} and {team_2_players} players, not five each"),
+            Issue::OutsideWindow { window_start, window_end } => write!(f, "played outside the time window {window_start} to {window_end}"),
+            Issue::Showmatch { event_name } => write!(f, "showmatch at {event_name}"),
+            Issue::UnknownEvent => write!(f, "event isn't in the event list"),
+            Issue::InvalidWinningTeam { winning_team } => write!(f, "winningTeam is {winning_team}, expected 1 or 2"),
+            Issue::DuplicateMatch { first_index } => write!(f, "duplicate of match #{first_index}"),
+            Issue::NoMaps => write!(f, "no maps"),
+            Issue::TiedMap { map_name, team_1_score, team_2_score } => write!(f, "{map_name} is tied {team_1_score}-{team_2_score}"),
+            Issue::WinnerDisagreesWithMaps { winning_team, team_1_maps, team_2_maps } => write!(f,
+                "winningTeam is {winning_team}, but the maps went {team_1_maps}-{team_2_maps}"
+            ),
+            Issue::DuplicateEvent => write!(f, "event is listed more than once"),
+            Issue::UnknownPrizeTeam { team_id, placement } => write!(f, "team {team_id} (placement {placement}) doesn't play any matches"),
+        }
+    }
+}
+
+/// The record an issue was found on. Matches don't have an ID in the feed, so we point at their position in the file instead.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "record", rename_all = "snake_case")]
+pub enum Record {
+    Match { index: usize, match_start_time: u32, event_id: usize, team_1_name: String, team_2_name: String },
+    Event { index: usize, event_id: usize, event_name: String },
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Record::Match { index, match_start_time, event_id, team_1_name, team_2_name } => write!(f,
+                "match #{index} {team_1_name} vs {team_2_name} (started {match_start_time}, event {event_id})"
+            ),
+            Record::Event { index, event_id, event_name } => write!(f, "event #{index} {event_name} ({event_id})"),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    #[serde(flatten)]
+    pub record: Record,
+    pub issue: Issue,
+}
+
+impl ValidationIssue {
+    // A record outside the window never makes it into the ranking, so there's no reason to refuse the whole feed over it
+    fn new(record: Record, issue: Issue, in_window: bool) -> Self {
+        let severity = match issue.severity() {
+            Severity::Error if !in_window => Severity::Dropped,
+            severity => severity,
+        };
+        Self { severity, record, issue }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ValidationReport {
+    pub matches_read: usize,
+    pub matches_kept: usize,
+    pub events_read: usize,
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|i| i.severity == Severity::Error)
+    }
+
+    /// Indices of the matches load_data skips
+    pub fn dropped_matches(&self) -> HashSet<usize> {
+        self.issues.iter()
+            .filter(|i| i.severity == Severity::Dropped)
+            .filter_map(|i| match i.record { Record::Match { index, .. } => Some(index), _ => None })
+            .collect()
+    }
+
+    /// The first issue load_data can't work around, as the crate Error it would return
+    pub fn first_error(&self) -> Option<Error> {
+        self.errors().next().map(|i| match (&i.record, &i.issue) {
+            (Record::Match { match_start_time, event_id, team_1_name, team_2_name, .. }, _) => Error::UnknownEvent {
+                event_id: *event_id,
+                match_start_time: *match_start_time,
+                team_1_name: team_1_name.clone(),
+                team_2_name: team_2_name.clone(),
+            },
+            (Record::Event { event_id, .. }, issue) => Error::InvalidEvent { event_id: *event_id, reason: issue.to_string() },
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(Error::Serialize)
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Read {} matches and {} events, kept {} matches", self.matches_read, self.events_read, self.matches_kept)?;
+
+        // Summary first, since there's usually a lot of matches outside the window
+        let mut counts: HashMap<(Severity, &str), usize> = HashMap::new();
+        for i in &self.issues {
+            *counts.entry((i.severity, i.issue.kind())).or_default() += 1;
+        }
+        let mut counts: Vec<_> = counts.into_iter().collect();
+        counts.sort();
+
+        for ((severity, kind), count) in counts {
+            writeln!(f, "{:8} | {kind:28} | {count:6}", format!("{severity:?}"))?;
+        }
+
+        for i in &self.issues {
+            if let Issue::OutsideWindow { .. } = i.issue { continue; }
+            writeln!(f, "{:?}: {}: {}", i.severity, i.record, i.issue)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the match data file and reports every record load_data would drop, refuse or should be suspicious of
+pub fn validate_data(file_path: &str, ranking_context: &RankingContext) -> Result<ValidationReport> {
+    Ok(Dataset::load(file_path)?.validate(ranking_context))
+}
+
+pub(crate) fn validate_match_data(match_data: &MatchData, ranking_context: &RankingContext) -> ValidationReport {
+    let mut report = ValidationReport {
+        matches_read: match_data.matches.len(),
+        events_read: match_data.events.len(),
+        ..Default::default()
+    };
+
+    // An event is in the window if any of its matches are
+    let events_in_window: HashSet<usize> = match_data.matches.iter()
+        .filter(|m| in_window(m, ranking_context))
+        .map(|m| m.event_id)
+        .collect();
+
+    // Events first, the first copy of an event wins
+    let mut events: HashMap<usize, &JsonEvent> = HashMap::new();
+    for (index, ev) in match_data.events.iter().enumerate() {
+        if events.contains_key(&ev.id) {
+            let record = Record::Event { index, event_id: ev.id, event_name: ev.name.clone() };
+            report.issues.push(ValidationIssue::new(record, Issue::DuplicateEvent, events_in_window.contains(&ev.id)));
+            continue;
+        }
+        events.insert(ev.id, ev);
+    }
+
+    let team_ids: HashSet<usize> = match_data.matches.iter().flat_map(|m| [m.team_1_id, m.team_2_id]).collect();
+    for (index, ev) in match_data.events.iter().enumerate() {
+        if !std::ptr::eq(events[&ev.id], ev) { continue; }
+
+        for pd in &ev.prize_distribution {
+            if team_ids.contains(&pd.team_id) { continue; }
+
+            let record = Record::Event { index, event_id: ev.id, event_name: ev.name.clone() };
+            let issue = Issue::UnknownPrizeTeam { team_id: pd.team_id, placement: pd.placement };
+            report.issues.push(ValidationIssue::new(record, issue, events_in_window.contains(&ev.id)));
+        }
+    }
+
+    let mut seen_matches: HashMap<(u32, usize, usize, usize), usize> = HashMap::new();
+    for (index, m) in match_data.matches.iter().enumerate() {
+        let record = Record::Match {
+            index,
+            match_start_time: m.match_start_time,
+            event_id: m.event_id,
+            team_1_name: m.team_1_name.clone(),
+            team_2_name: m.team_2_name.clone(),
+        };
+
+        let issues = match_issues(m, index, &events, &mut seen_matches, ranking_context);
+        let dropped = issues.iter().any(|i| i.severity() != Severity::Warning);
+
+        for issue in issues {
+            report.issues.push(ValidationIssue::new(record.clone(), issue, in_window(m, ranking_context)));
+        }
+
+        if !dropped { report.matches_kept += 1; }
+    }
+
+    report
+}
+
+fn in_window(m: &Match, ranking_context: &RankingContext) -> bool {
+    (ranking_context.time_window_start..=ranking_context.time_window_end).contains(&m.match_start_time)
+}
+
+fn match_issues(
+    m: &Match,
+    index: usize,
+    events: &HashMap<usize, &JsonEvent>,
+    seen_matches: &mut HashMap<(u32, usize, usize, usize), usize>,
+    ranking_context: &RankingContext,
+) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    match events.get(&m.event_id) {
+        None => issues.push(Issue::UnknownEvent),
+        // Obviously imperfect
+        Some(ev) if ev.name.to_lowercase().contains("showmatch") => issues.push(Issue::Showmatch { event_name: ev.name.clone() }),
+        Some(_) => (),
+    }
+
+    if m.team_1_players.len() != 5 || m.team_2_players.len() != 5 {
+        issues.push(Issue::IncompleteLineup { team_1_players: m.team_1_players.len(), team_2_players: m.team_2_players.len() });
+    }
+
+    if !in_window(m, ranking_context) {
+        issues.push(Issue::OutsideWindow { window_start: ranking_context.time_window_start, window_end: ranking_context.time_window_end });
+    }
+
+    if m.winning_team != 1 && m.winning_team != 2 {
+        issues.push(Issue::InvalidWinningTeam { winning_team: m.winning_team });
+    }
+
+    // The same teams at the same time at the same event, in either order
+    let key = (m.match_start_time, m.team_1_id.min(m.team_2_id), m.team_1_id.max(m.team_2_id), m.event_id);
+    match seen_matches.get(&key) {
+        Some(&first_index) => issues.push(Issue::DuplicateMatch { first_index }),
+        None => { seen_matches.insert(key, index); }
+    }
+
+    if m.maps.is_empty() {
+        issues.push(Issue::NoMaps);
+    }
+
+    let mut team_1_maps = 0;
+    let mut team_2_maps = 0;
+    for map in &m.maps {
+        if map.team_1_score > map.team_2_score { team_1_maps += 1; }
+        if map.team_2_score > map.team_1_score { team_2_maps += 1; }
+
+        if map.team_1_score == map.team_2_score {
+            issues.push(Issue::TiedMap { map_name: map.map_name.clone(), team_1_score: map.team_1_score, team_2_score: map.team_2_score });
+        }
+    }
+
+    let maps_winner = match team_1_maps.cmp(&team_2_maps) {
+        std::cmp::Ordering::Greater => Some(1),
+        std::cmp::Ordering::Less    => Some(2),
+        std::cmp::Ordering::Equal   => None,
+    };
+    if !m.maps.is_empty() && (m.winning_team == 1 || m.winning_team == 2) && maps_winner != Some(m.winning_team) {
+        issues.push(Issue::WinnerDisagreesWithMaps { winning_team: m.winning_team, team_1_maps, team_2_maps });
+    }
+
+    issues
+}
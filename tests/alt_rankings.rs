@@ -0,0 +1,101 @@
+mod common;
+
+use alternative_vrs::*;
+use alternative_vrs::alt_rankings::STARTING_RATING;
+use common::*;
+
+fn teams() -> Vec<Team> {
+    vec![team(0, "Alpha", 1, 0.0), team(1, "Bravo", 11, 0.0), team(2, "Charlie", 21, 0.0)]
+}
+
+fn map(team_1_score: u16, team_2_score: u16) -> Map {
+    Map { map_name: "de_mirage".to_string(), team_1_score, team_2_score }
+}
+
+// Alpha beats Bravo 2-1, then Bravo beats Alpha 2-0. Charlie never plays
+fn matches() -> Vec<Match> {
+    let mut first = indexed_match(WINDOW_START, 0, 1, 0, 0);
+    first.maps = vec![map(13, 3), map(11, 13), map(13, 11)];
+    let second = indexed_match(WINDOW_START + DAY, 0, 1, 1, 2);
+    vec![first, second]
+}
+
+#[test]
+fn pure_elo_map_starts_everyone_equal() {
+    let ranking_context = RankingContext::default();
+    let mut ranked = teams();
+    gen_rank_pure_elo_map(&matches(), &[], &mut ranked, &ranking_context).unwrap();
+
+    // The same as the head-to-head stage from 1500
+    let mut expected = teams();
+    for t in expected.iter_mut() { t.elo = STARTING_RATING; }
+    map_elo_adjustments(&matches(), &mut expected, &ranking_context).unwrap();
+
+    assert_eq!(ranked.iter().map(|t| t.elo).collect::<Vec<_>>(), expected.iter().map(|t| t.elo).collect::<Vec<_>>());
+    assert_eq!(ranked[2].elo, STARTING_RATING);
+    assert!((ranked.iter().map(|t| t.elo).sum::<f64>() - 3.0 * STARTING_RATING).abs() < 1e-9);
+}
+
+#[test]
+fn pure_elo_rates_whole_matches() {
+    let ranking_context = RankingContext::default();
+    let mut ranked = teams();
+    gen_rank_pure_elo(&matches(), &[], &mut ranked, &ranking_context).unwrap();
+
+    // One win each, so the first one is worth exactly as much as the second one takes back, plus the change in odds
+    let first = elo_result(STARTING_RATING, STARTING_RATING, &ranking_context);
+    let second = elo_result(STARTING_RATING - first, STARTING_RATING + first, &ranking_context);
+    assert!((ranked[0].elo - (STARTING_RATING + first - second)).abs() < 1e-9);
+    assert!((ranked[1].elo - (STARTING_RATING - first + second)).abs() < 1e-9);
+}
+
+#[test]
+fn org_rounds_weighs_maps_by_the_round_difference() {
+    let ranking_context = RankingContext::default();
+    let m = indexed_match(WINDOW_START, 0, 1, 0, 0);
+
+    let mut ranked = teams();
+    ranked[0].elo = 1500.0;
+    ranked[1].elo = 1500.0;
+
+    let mut system = OrgRounds;
+    system.update_map(&m, &map(13, 7), &mut ranked, &ranking_context);
+    assert!((ranked[0].elo - (1500.0 + 6.0 * elo_result(1500.0, 1500.0, &ranking_context))).abs() < 1e-9);
+
+    // A tied map doesn't move anything
+    let before = ranked[0].elo;
+    system.update_map(&m, &map(15, 15), &mut ranked, &ranking_context);
+    assert_eq!(ranked[0].elo, before);
+}
+
+#[test]
+fn every_model_can_be_picked_from_the_config() {
+    for (name, kind) in [("pure_elo_map", RatingSystemKind::PureEloMap), ("pure_elo", RatingSystemKind::PureElo), ("org_rounds", RatingSystemKind::OrgRounds)] {
+        let ranking_context: RankingContext = serde_json::from_value(serde_json::json!({ "rating_system": { "system": name }, "top_outlier_count": 1 })).unwrap();
+        assert_eq!(ranking_context.rating_system, kind);
+
+        let mut ranked = teams();
+        let system = rank(&matches(), &[], &mut ranked, &ranking_context).unwrap();
+        assert_eq!(system.kind(), kind);
+        assert!(analyze_fit(&ranked, &matches(), &ranking_context, false).is_ok());
+    }
+}
+
+#[test]
+fn every_map_by_map_system_gives_a_tied_map_to_team_2() {
+    let ranking_context = RankingContext::default();
+    let mut tied = indexed_match(WINDOW_START, 0, 1, 0, 1);
+    tied.maps = vec![map(15, 15)];
+
+    let mut expected = teams();
+    map_elo_adjustments(std::slice::from_ref(&tied), &mut expected, &ranking_context).unwrap();
+    assert!(expected[1].elo > expected[0].elo);
+
+    let systems: [Box<dyn RatingSystem>; 2] = [Box::new(AlternativeVrs), Box::new(PureEloMap)];
+    for mut system in systems {
+        let mut ranked = teams();
+        system.update_map(&tied, &tied.maps[0], &mut ranked, &ranking_context);
+        assert_eq!(ranked[0].elo, expected[0].elo);
+        assert_eq!(ranked[1].elo, expected[1].elo);
+    }
+}
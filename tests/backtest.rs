@@ -0,0 +1,99 @@
+mod common;
+
+use alternative_vrs::*;
+use common::*;
+
+// Alpha beats everyone and Charlie loses to everyone up to the cutoff at the end of day 40, and after it too,
+// except for late_result. Delta only shows up after the cutoff.
+fn feed(late_result: usize) -> Feed {
+    let mut feed = Feed::default().event(7, &[(1, 101, 1000.0, false), (2, 102, 500.0, false), (3, 103, 250.0, false)]);
+    for day in 1..=50 {
+        let time = WINDOW_START + day * DAY;
+        feed = feed
+            .match_(time, 7, ALPHA, BRAVO, 1)
+            .match_(time + 1, 7, BRAVO, CHARLIE, 1)
+            .match_(time + 2, 7, CHARLIE, ALPHA, 2);
+    }
+    feed.match_(WINDOW_START + 41 * DAY + 3, 7, ALPHA, CHARLIE, late_result)
+        .match_(WINDOW_START + 42 * DAY + 3, 7, DELTA, ALPHA, 1)
+}
+
+fn run(feed: Feed) -> Backtest {
+    let dataset = Dataset::load(&feed.write()).unwrap();
+    let ranking_context = RankingContext { time_grace_period: 0, ..context() };
+    backtest(&dataset, &ranking_context, &[WINDOW_START + 40 * DAY + 2], 60, 5, 10).unwrap()
+}
+
+#[test]
+fn folds_train_before_and_test_after_the_cutoff() {
+    let result = run(feed(1));
+    assert_eq!(result.folds.len(), 1);
+
+    let fold = &result.folds[0];
+    assert_eq!(fold.training_matches, 120);
+    // Days 41 to 45, plus the two extra matches
+    assert_eq!(fold.test_matches, 17);
+    assert_eq!(fold.unmatched, 1);
+    assert_eq!(fold.scores.predictions, 16);
+
+    // Alpha and Bravo keep winning, so the ratings should see it coming
+    assert!(fold.predictions.iter().all(|p| (p.probability > 0.5) == p.outcome));
+    assert!(fold.scores.log_loss < 2f64.ln());
+    assert_eq!(result.overall, fold.scores);
+}
+
+#[test]
+fn results_after_the_cutoff_do_not_leak_into_the_ratings() {
+    let won = run(feed(1));
+    let lost = run(feed(2));
+
+    let probabilities = |b: &Backtest| b.folds[0].predictions.iter().map(|p| p.probability).collect::<Vec<_>>();
+    assert_eq!(probabilities(&won), probabilities(&lost));
+    assert!(won.overall.log_loss < lost.overall.log_loss);
+}
+
+#[test]
+fn cutoffs_without_enough_data_are_skipped() {
+    let dataset = Dataset::load(&feed(1).write()).unwrap();
+    let ranking_context = RankingContext { time_grace_period: 0, ..context() };
+    let result = backtest(&dataset, &ranking_context, &[WINDOW_START, WINDOW_START + 40 * DAY + 2], 60, 5, 10).unwrap();
+
+    assert_eq!(result.skipped.len(), 1);
+    assert_eq!(result.folds.len(), 1);
+
+    let mut buffer = Vec::new();
+    result.write_csv(&mut buffer).unwrap();
+    assert_eq!(csv::Reader::from_reader(&buffer[..]).records().count(), 1);
+}
+
+#[test]
+fn folds_the_context_refuses_are_skipped() {
+    let dataset = Dataset::load(&feed(1).write()).unwrap();
+    let ranking_context = RankingContext { time_grace_period: 30 * DAY, ..context() };
+
+    // The first training window is cut off at the beginning of time, leaving less than the grace period
+    let cutoffs = [20 * DAY, WINDOW_START + 40 * DAY + 2];
+    let result = backtest(&dataset, &ranking_context, &cutoffs, 60, 5, 10).unwrap();
+    assert_eq!(result.skipped.len(), 1);
+    assert_eq!(result.skipped[0].0, 20 * DAY);
+    assert!(result.skipped[0].1.contains("time_grace_period"));
+    assert_eq!(result.folds.len(), 1);
+    assert_eq!(result.folds[0].cutoff, cutoffs[1]);
+
+    // A window shorter than the grace period skips every fold
+    let result = backtest(&dataset, &ranking_context, &cutoffs, 20, 5, 10).unwrap();
+    assert_eq!(result.skipped.len(), 2);
+    assert!(result.folds.is_empty());
+}
+
+#[test]
+fn huge_windows_and_horizons_run_to_the_ends_of_time() {
+    let dataset = Dataset::load(&feed(1).write()).unwrap();
+    let ranking_context = RankingContext { time_grace_period: 0, ..context() };
+    let result = backtest(&dataset, &ranking_context, &[WINDOW_START + 40 * DAY + 2], u32::MAX, u32::MAX, 10).unwrap();
+
+    // Days 41 to 50, plus the two extra matches
+    let fold = &result.folds[0];
+    assert_eq!(fold.training_matches, 120);
+    assert_eq!(fold.test_matches, 32);
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use alternative_vrs::metadata_path;
+use alternative_vrs::util::{parse_date, parse_end_date};
+use common::*;
+use std::process::Command;
+
+// Runs the binary and parses what it printed as JSON
+fn run_json(args: &[&str]) -> serde_json::Value {
+    let output = Command::new(env!("CARGO_BIN_EXE_alternative_vrs")).args(args).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn dates_parse_as_midnight_utc_or_timestamps() {
+    assert_eq!(parse_date("1970-01-02"), Some(DAY));
+    assert_eq!(parse_date("2023-08-29"), Some(1693267200));
+    assert_eq!(parse_date("1693330518"), Some(1693330518));
+
+    // The last day of every month, leap years included
+    assert_eq!(parse_date("2024-02-29"), Some(parse_date("2024-03-01").unwrap() - DAY));
+    assert_eq!(parse_date("2000-02-29"), Some(parse_date("2000-03-01").unwrap() - DAY));
+    assert_eq!(parse_date("2023-04-30"), Some(parse_date("2023-05-01").unwrap() - DAY));
+
+    for invalid in ["2023-13-01", "2023-08-32", "2023-02-29", "2023-02-31", "2023-04-31", "1900-02-29", "2023-08", "2023-08-29-01", "yesterday", ""] {
+        assert_eq!(parse_date(invalid), None, "{invalid}");
+    }
+}
+
+#[test]
+fn an_end_date_includes_the_whole_day() {
+    assert_eq!(parse_end_date("2023-08-29"), Some(1693267200 + DAY - 1));
+    assert_eq!(parse_end_date("1693330518"), Some(1693330518));
+    assert_eq!(parse_end_date("2023-02-30x"), None);
+
+    // The last match in the sample was played at 17:35 on the 29th
+    assert!(parse_end_date("2023-08-29").unwrap() > WINDOW_END);
+}
+
+#[test]
+fn rank_takes_the_window_from_the_flags() {
+    let path = Feed::default()
+        .event(7, &[(1, ALPHA.0, 1000.0, false)])
+        .match_(WINDOW_START + DAY, 7, ALPHA, BRAVO, 1)
+        .match_(WINDOW_END, 7, BRAVO, ALPHA, 2)
+        .write();
+
+    let json = run_json(&["rank", "--data", &path, "--end", "2023-08-29", "--start", "2023-03-01", "--top-outlier-count", "1", "--format", "json"]);
+    let ranking_context = &json["metadata"]["ranking_context"];
+    assert_eq!(ranking_context["time_window_end"], parse_end_date("2023-08-29").unwrap());
+    assert_eq!(ranking_context["time_window_start"], parse_date("2023-03-01").unwrap());
+
+    // Without --end the window ends with the last match, and flags override the model
+    let json = run_json(&["rank", "--data", &path, "--window-days", "90", "--elo-k", "20", "--top-outlier-count", "1", "--format", "json"]);
+    let ranking_context = &json["metadata"]["ranking_context"];
+    assert_eq!(ranking_context["time_window_end"], WINDOW_END);
+    assert_eq!(ranking_context["time_window_start"], WINDOW_END - 90 * DAY);
+    assert_eq!(ranking_context["elo_k"], 20.0);
+}
+
+#[test]
+fn invalid_flags_are_rejected() {
+    let output = Command::new(env!("CARGO_BIN_EXE_alternative_vrs")).args(["rank", "--end", "2023-13-01"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("neither a YYYY-MM-DD date nor a unix timestamp"));
+}
+
+#[test]
+fn compare_names_added_systems_by_their_flag_value() {
+    let path = round_robin_feed(6).write();
+    let args = ["compare", "--data", &path, "--top-outlier-count", "1", "--system", "glicko2", "--system", "pure-elo", "--format", "csv"];
+    let output = Command::new(env!("CARGO_BIN_EXE_alternative_vrs")).args(args).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let csv = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        csv.lines().next().unwrap(),
+        "name,core,rank_configured,elo_configured,rank_glicko2,elo_glicko2,rank_pure-elo,elo_pure-elo,largest_move,flagged"
+    );
+}
+
+#[test]
+fn explain_and_ledger_refuse_other_rating_systems() {
+    let path = round_robin_feed(6).write();
+    for command in ["explain", "ledger"] {
+        let args = [command, "--data", &path, "--team", "Alpha", "--top-outlier-count", "1", "--rating-system", "glicko2"];
+        let output = Command::new(env!("CARGO_BIN_EXE_alternative_vrs")).args(args).output().unwrap();
+        assert!(!output.status.success(), "{command}");
+        assert!(String::from_utf8_lossy(&output.stderr).contains("only works with the Alternative VRS"), "{command}");
+    }
+}
+
+#[test]
+fn csv_standings_need_an_output_file() {
+    let path = round_robin_feed(6).write();
+    let output = Command::new(env!("CARGO_BIN_EXE_alternative_vrs"))
+        .args(["rank", "--data", &path, "--top-outlier-count", "1", "--format", "csv"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--format csv needs --output"));
+
+    let csv = temp_file("csv", "");
+    let output = Command::new(env!("CARGO_BIN_EXE_alternative_vrs"))
+        .args(["rank", "--data", &path, "--top-outlier-count", "1", "--format", "csv", "--output", &csv])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(std::path::Path::new(&metadata_path(&csv)).exists());
+}
+
+#[test]
+fn history_takes_a_window_longer_than_time_itself() {
+    let path = round_robin_feed(6).write();
+    let json = run_json(&["history", "--data", &path, "--top-outlier-count", "1", "--time-grace-period", "0", "--window-days", "100000", "--format", "json"]);
+    // The cutoffs start at the beginning of time, so nearly all of them are skipped
+    assert_eq!(json["window_days"], 100000);
+    assert!(json["skipped"].as_array().unwrap().len() > 1000);
+}
+
+#[test]
+fn backtest_takes_windows_and_horizons_longer_than_time_itself() {
+    let path = round_robin_feed(6).write();
+    let args = ["backtest", "--data", &path, "--top-outlier-count", "1", "--window-days", "100000", "--horizon-days", "100000", "--format", "json"];
+    let json = run_json(&args);
+    assert_eq!(json["window_days"], 100000);
+    assert_eq!(json["horizon_days"], 100000);
+}
+
+#[test]
+fn a_window_longer_than_time_itself_starts_at_the_beginning() {
+    let path = round_robin_feed(6).write();
+    let json = run_json(&["rank", "--data", &path, "--window-days", "100000", "--time-grace-period", "0", "--top-outlier-count", "1", "--format", "json"]);
+    assert_eq!(json["metadata"]["ranking_context"]["time_window_start"], 0);
+}
+
+#[test]
+fn only_the_weighted_rule_takes_a_fractional_overlap() {
+    let path = round_robin_feed(6).write();
+    let output = Command::new(env!("CARGO_BIN_EXE_alternative_vrs"))
+        .args(["rank", "--data", &path, "--top-outlier-count", "1", "--core-min-overlap", "2.5", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("core_min_overlap can't be set to 2.5"));
+
+    let json = run_json(&["rank", "--data", &path, "--top-outlier-count", "1", "--core-matching", "weighted", "--core-min-overlap", "2.5", "--format", "json"]);
+    assert_eq!(json["metadata"]["ranking_context"]["core_matching"]["min_overlap"], 2.5);
+
+    let json = run_json(&["rank", "--data", &path, "--top-outlier-count", "1", "--core-matching", "rolling", "--core-min-overlap", "4", "--format", "json"]);
+    assert_eq!(json["metadata"]["ranking_context"]["core_matching"]["min_overlap"], 4);
+}
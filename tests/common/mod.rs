@@ -0,0 +1,178 @@
+#![allow(dead_code)]
+
+use alternative_vrs::*;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub const DAY: u32 = 24 * 60 * 60;
+pub const WINDOW_END: u32 = 1693330518;
+pub const WINDOW_START: u32 = WINDOW_END - 180 * DAY;
+
+pub fn context() -> RankingContext {
+    RankingContext {
+        time_window_start: WINDOW_START,
+        time_window_end: WINDOW_END,
+        top_outlier_count: 1,
+        min_matches_for_ranking: 0,
+        min_wins_for_ranking: 0,
+        ..Default::default()
+    }
+}
+
+// Five players with IDs first_id..first_id + 5
+pub fn lineup(first_id: u16) -> Vec<Player> {
+    (first_id..first_id + 5).map(player).collect()
+}
+
+pub fn player(id: u16) -> Player {
+    Player {
+        player_id: id,
+        nick: format!("player{id}"),
+        country: "Norway".to_string(),
+        country_iso: "NO".to_string(),
+    }
+}
+
+pub fn team(id: usize, name: &str, first_player_id: u16, elo: f64) -> Team {
+    let players = lineup(first_player_id);
+    let mut team = Team::new(TeamId(id), name.to_string(), [
+        players[0].clone(), players[1].clone(), players[2].clone(), players[3].clone(), players[4].clone(),
+    ]);
+    team.elo = elo;
+    team
+}
+
+// A match between two team IDs where the winner takes every map 13-7. The HLTV IDs are the same as ours.
+pub fn indexed_match(start_time: u32, team_1: usize, team_2: usize, winner: usize, maps: usize) -> Match {
+    let map = if winner == team_1 { (13, 7) } else { (7, 13) };
+
+    Match {
+        match_start_time: start_time,
+        team_1_id: team_1,
+        team_2_id: team_2,
+        team_1_name: String::new(),
+        team_2_name: String::new(),
+        team_1_players: Vec::new(),
+        team_2_players: Vec::new(),
+        event_id: 0,
+        maps: (0..maps).map(|_| Map { map_name: "de_nuke".to_string(), team_1_score: map.0, team_2_score: map.1 }).collect(),
+        winning_team: if winner == team_1 { 1 } else { 2 },
+        information_context: 1.0,
+        team_1: TeamId(team_1),
+        team_2: TeamId(team_2),
+        winner: TeamId(winner),
+        event: EventId(0),
+    }
+}
+
+// (HLTV team ID, name, first player ID), for Feed::match_
+pub const ALPHA: (usize, &str, u16) = (101, "Alpha", 1);
+pub const BRAVO: (usize, &str, u16) = (102, "Bravo", 11);
+pub const CHARLIE: (usize, &str, u16) = (103, "Charlie", 21);
+pub const DELTA: (usize, &str, u16) = (104, "Delta", 31);
+
+// Event 7 pays Alpha, Bravo and Charlie in that order. Then every day they play each other once: Alpha beats Bravo
+// except every third day, Bravo beats Charlie and Alpha beats Charlie
+pub fn round_robin_feed(days: u32) -> Feed {
+    let mut feed = Feed::default().event(7, &[(1, 101, 1000.0, false), (2, 102, 500.0, false), (3, 103, 250.0, false)]);
+    for day in 1..=days {
+        feed = feed
+            .match_(WINDOW_START + day * DAY, 7, ALPHA, BRAVO, if day % 3 == 0 { 2 } else { 1 })
+            .match_(WINDOW_START + day * DAY + 1, 7, BRAVO, CHARLIE, 1)
+            .match_(WINDOW_START + day * DAY + 2, 7, CHARLIE, ALPHA, 2);
+    }
+    feed
+}
+
+// Six days of round_robin_feed
+pub fn round_robin_dataset() -> Dataset {
+    Dataset::load(&round_robin_feed(6).write()).unwrap()
+}
+
+// Builds an HLTV style feed in memory and writes it to a temporary file for load_data
+#[derive(Default)]
+pub struct Feed {
+    matches: Vec<Value>,
+    events: Vec<Value>,
+}
+
+impl Feed {
+    pub fn event(mut self, event_id: usize, prize_distribution: &[(u32, usize, f64, bool)]) -> Self {
+        let prize_distribution: Vec<Value> = prize_distribution.iter()
+            .map(|(placement, team_id, prize, shared)| json!({
+                "placement": placement, "teamId": team_id.to_string(), "prize": prize, "shared": shared,
+            }))
+            .collect();
+
+        self.events.push(json!({
+            "eventId": event_id.to_string(),
+            "eventName": format!("Event {event_id}"),
+            "prizePool": "",
+            "lan": true,
+            "prizeDistribution": prize_distribution,
+        }));
+        self
+    }
+
+    // The prize pool HLTV lists for the last event, e.g. "$1,000,000"
+    pub fn prize_pool(mut self, prize_pool: &str) -> Self {
+        self.events.last_mut().unwrap()["prizePool"] = json!(prize_pool);
+        self
+    }
+
+    // team_1 and team_2 are (HLTV team ID, name, first player ID). winning_team is 1 or 2, and wins a single map
+    pub fn match_(self, start_time: u32, event_id: usize, team_1: (usize, &str, u16), team_2: (usize, &str, u16), winning_team: usize) -> Self {
+        let score = if winning_team == 1 { (13, 5) } else { (5, 13) };
+        self.match_with_maps(start_time, event_id, team_1, team_2, winning_team, &[score])
+    }
+
+    // Same as match_, with any winningTeam and map scores, including ones that don't make sense
+    pub fn match_with_maps(
+        mut self,
+        start_time: u32,
+        event_id: usize,
+        team_1: (usize, &str, u16),
+        team_2: (usize, &str, u16),
+        winning_team: usize,
+        maps: &[(u16, u16)],
+    ) -> Self {
+        let maps: Vec<Value> = maps.iter()
+            .map(|(team_1_score, team_2_score)| json!({ "mapName": "de_anubis", "team1Score": team_1_score, "team2Score": team_2_score }))
+            .collect();
+
+        self.matches.push(json!({
+            "matchStartTime": start_time,
+            "team1Id": team_1.0.to_string(),
+            "team2Id": team_2.0.to_string(),
+            "team1Name": team_1.1,
+            "team2Name": team_2.1,
+            "team1Players": lineup(team_1.2).iter().map(player_json).collect::<Vec<_>>(),
+            "team2Players": lineup(team_2.2).iter().map(player_json).collect::<Vec<_>>(),
+            "eventId": event_id.to_string(),
+            "maps": maps,
+            "winningTeam": winning_team.to_string(),
+        }));
+        self
+    }
+
+    pub fn write(&self) -> String {
+        temp_file("json", &json!({ "matches": self.matches, "events": self.events }).to_string())
+    }
+}
+
+// Writes contents to a new file in the temporary directory, with the given extension, and returns its path
+pub fn temp_file(extension: &str, contents: &str) -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("avrs_test_{}_{}.{extension}", std::process::id(), COUNTER.fetch_add(1, Ordering::SeqCst)));
+
+    fs::write(&path, contents).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+fn player_json(p: &Player) -> Value {
+    json!({ "playerId": p.player_id.to_string(), "nick": p.nick, "country": p.country, "countryIso": p.country_iso })
+}
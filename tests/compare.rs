@@ -0,0 +1,87 @@
+mod common;
+
+use alternative_vrs::*;
+use common::*;
+
+fn models() -> Vec<(String, RankingContext)> {
+    let ranking_context = RankingContext { time_grace_period: 0, ..context() };
+    vec![
+        ("alternative".to_string(), ranking_context.clone()),
+        ("original".to_string(), RankingContext { rating_system: RatingSystemKind::OriginalVrs, ..ranking_context }),
+    ]
+}
+
+#[test]
+fn every_model_ranks_the_same_teams() {
+    let dataset = round_robin_dataset();
+    let comparison = compare_models(&dataset, &models(), 10, 5).unwrap();
+
+    assert_eq!(comparison.models.len(), 2);
+    assert_eq!(comparison.teams.len(), 3);
+    assert!(comparison.teams.iter().all(|t| t.ranks.iter().all(|r| r.is_some())));
+
+    // The first model's ranks are the ones rank shows
+    let (_, ranking_context) = &models()[0];
+    let (matches, events, mut teams) = dataset.window(ranking_context).unwrap();
+    gen_rank_new(&matches, &events, &mut teams, ranking_context).unwrap();
+    let expected: Vec<(usize, String)> = ranked_teams(&teams, ranking_context).into_iter().map(|(rank, t)| (rank, t.name.clone())).collect();
+    let compared: Vec<(usize, String)> = comparison.teams.iter().map(|t| (t.ranks[0].unwrap(), t.name.clone())).collect();
+    assert_eq!(compared, expected);
+
+    // And the fit is the one fit prints
+    let fit = &comparison.models[0].fit;
+    assert_eq!(fit.error, analyze_fit(&teams, &matches, ranking_context, false).unwrap());
+}
+
+#[test]
+fn teams_that_move_too_far_are_flagged() {
+    let dataset = round_robin_dataset();
+    let lenient = compare_models(&dataset, &models(), 10, 5).unwrap();
+    assert_eq!(lenient.flagged().count(), 0);
+
+    let strict = compare_models(&dataset, &models(), 10, 0).unwrap();
+    for t in &strict.teams {
+        let moved = t.ranks[0].unwrap().abs_diff(t.ranks[1].unwrap());
+        assert_eq!(t.largest_move, Some(moved));
+        assert_eq!(t.flagged, moved > 0);
+    }
+
+    // The same model twice never moves anything
+    let (name, ranking_context) = models().remove(0);
+    let same = compare_models(&dataset, &[(name.clone(), ranking_context.clone()), (name, ranking_context)], 10, 0).unwrap();
+    assert!(same.teams.iter().all(|t| t.largest_move == Some(0) && !t.flagged));
+}
+
+#[test]
+fn csv_has_a_rank_and_elo_column_per_model() {
+    let comparison = compare_models(&round_robin_dataset(), &models(), 10, 5).unwrap();
+
+    let mut csv = Vec::new();
+    comparison.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+
+    let mut lines = csv.lines();
+    // Plain CSV, the header comes first
+    assert_eq!(lines.next().unwrap(), "name,core,rank_alternative,elo_alternative,rank_original,elo_original,largest_move,flagged");
+    assert_eq!(lines.count(), 3);
+
+    let mut json = Vec::new();
+    comparison.write_json(&mut json).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    assert_eq!(json["models"][1]["ranking_context"]["rating_system"]["system"], "original_vrs");
+}
+
+#[test]
+fn every_model_is_scored_in_the_first_models_window() {
+    let dataset = round_robin_dataset();
+    let mut models = models();
+    models[1].1.time_window_start = WINDOW_START + 3 * DAY;
+    models[1].1.time_window_end = WINDOW_START + 5 * DAY;
+
+    let comparison = compare_models(&dataset, &models, 10, 5).unwrap();
+    let (first, other) = (&comparison.models[0], &comparison.models[1]);
+    assert_eq!(other.ranking_context.time_window_start, first.ranking_context.time_window_start);
+    assert_eq!(other.ranking_context.time_window_end, first.ranking_context.time_window_end);
+    assert_eq!(other.evaluation.matches.predictions, first.evaluation.matches.predictions);
+    assert_eq!(other.ranking_context.rating_system, RatingSystemKind::OriginalVrs);
+}
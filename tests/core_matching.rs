@@ -0,0 +1,99 @@
+mod common;
+
+use alternative_vrs::*;
+use common::*;
+
+// Bravo's players are kept clear of every lineup Alpha plays with
+const BRAVO: (usize, &str, u16) = (102, "Bravo", 51);
+
+// Alpha replaces one player at a time: 1-5, then 2-6, 3-7 and finally 4-8. The oldest lineup only shares two
+// players with the newest one.
+fn rebuilt_team() -> String {
+    let mut feed = Feed::default().event(7, &[]);
+    for (day, first_player) in (1..=4).enumerate() {
+        feed = feed.match_(WINDOW_START + (day as u32 + 1) * DAY, 7, (101, "Alpha", first_player), BRAVO, 1);
+    }
+    feed.write()
+}
+
+fn load_with(core_matching: CoreMatching) -> (Vec<Team>, CoreLog) {
+    let ranking_context = RankingContext { core_matching, ..context() };
+    let mut matcher = core_matching.matcher();
+    let (_, _, teams, core_log) = load_data_with(rebuilt_team(), &ranking_context, matcher.as_mut()).unwrap();
+    (teams, core_log)
+}
+
+fn alpha_cores(teams: &[Team]) -> usize {
+    teams.iter().filter(|t| t.hltv_ids.contains(&101)).count()
+}
+
+#[test]
+fn first_seen_core_splits_a_gradually_rebuilt_team() {
+    let (teams, core_log) = load_with(CoreMatching::FirstSeen { min_overlap: 3 });
+    assert_eq!(alpha_cores(&teams), 2);
+
+    let newest = teams.iter().find(|t| t.name == "Alpha").unwrap().id;
+    let split: Vec<_> = core_log.decisions.iter().filter(|d| matches!(d.kind, CoreDecisionKind::Split { .. })).collect();
+    assert_eq!(split.len(), 1);
+    assert_eq!(split[0].kind, CoreDecisionKind::Split { previous: newest });
+    assert_eq!(split[0].match_start_time, WINDOW_START + DAY);
+    assert_eq!(split[0].hltv_team_id, 101);
+}
+
+#[test]
+fn rolling_core_follows_a_gradually_rebuilt_team() {
+    let (teams, core_log) = load_with(CoreMatching::Rolling { min_overlap: 3 });
+    assert_eq!(alpha_cores(&teams), 1);
+
+    // Every older lineup shares four players with the one after it
+    let merged: Vec<_> = core_log.decisions.iter().filter(|d| d.hltv_team_id == 101 && d.kind != CoreDecisionKind::New).collect();
+    assert_eq!(merged.len(), 3);
+    assert!(merged.iter().all(|d| d.kind == CoreDecisionKind::Merged { overlap: 4.0 }));
+}
+
+#[test]
+fn weighted_overlap_follows_a_gradually_rebuilt_team() {
+    let (teams, _) = load_with(CoreMatching::Weighted { last_n: 3, min_overlap: 3.0 });
+    assert_eq!(alpha_cores(&teams), 1);
+
+    // Asking for more than the four players each step keeps makes every lineup its own team
+    let (teams, _) = load_with(CoreMatching::Weighted { last_n: 3, min_overlap: 4.5 });
+    assert_eq!(alpha_cores(&teams), 4);
+}
+
+#[test]
+fn identical_lineups_are_left_out_of_the_log() {
+    let (_, core_log) = load_with(CoreMatching::FirstSeen { min_overlap: 3 });
+
+    let bravo: Vec<_> = core_log.decisions.iter().filter(|d| d.hltv_team_id == BRAVO.0).collect();
+    assert_eq!(bravo.len(), 1);
+    assert_eq!(bravo[0].kind, CoreDecisionKind::New);
+}
+
+// Never considers two lineups the same team
+struct EveryLineupIsNew;
+
+impl CoreMatcher for EveryLineupIsNew {
+    fn rule(&self) -> CoreMatching {
+        CoreMatching::default()
+    }
+
+    fn find_team(&self, _teams: &[Team], _lineup: &[Player]) -> Option<(TeamId, f64)> {
+        None
+    }
+}
+
+#[test]
+fn load_data_takes_any_core_matcher() {
+    let (matches, _, teams, _) = load_data_with(rebuilt_team(), &context(), &mut EveryLineupIsNew).unwrap();
+    assert_eq!(teams.len(), 2 * matches.len());
+}
+
+#[test]
+fn core_matching_is_validated() {
+    let ranking_context = RankingContext { core_matching: CoreMatching::Rolling { min_overlap: 6 }, ..context() };
+    assert!(matches!(load_data(rebuilt_team(), &ranking_context), Err(Error::Context(ContextError::InvalidCoreMatching(_)))));
+
+    let ranking_context = RankingContext { core_matching: CoreMatching::Weighted { last_n: 0, min_overlap: 3.0 }, ..context() };
+    assert!(ranking_context.validate().is_err());
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use alternative_vrs::*;
+use common::*;
+
+// Alpha and Bravo play early in the window, Charlie only shows up late
+fn feed() -> String {
+    Feed::default()
+        .event(7, &[(1, 101, 1000.0, false), (2, 102, 500.0, false)])
+        .event(8, &[(1, 103, 2000.0, false)])
+        .match_(WINDOW_START + DAY, 7, ALPHA, BRAVO, 1)
+        .match_(WINDOW_START + 2 * DAY, 7, ALPHA, BRAVO, 2)
+        .match_(WINDOW_START + 100 * DAY, 8, CHARLIE, BRAVO, 1)
+        .match_(WINDOW_START + 101 * DAY, 8, CHARLIE, ALPHA, 1)
+        .write()
+}
+
+fn names(teams: &[Team]) -> Vec<&str> {
+    teams.iter().map(|t| t.name.as_str()).collect()
+}
+
+#[test]
+fn window_matches_load_data() {
+    let path = feed();
+    let dataset = Dataset::load(&path).unwrap();
+
+    let (matches, events, teams) = dataset.window(&context()).unwrap();
+    let (loaded_matches, loaded_events, loaded_teams) = load_data(path, &context()).unwrap();
+
+    let times = |matches: &[Match]| matches.iter().map(|m| (m.match_start_time, m.team_1, m.team_2, m.winner)).collect::<Vec<_>>();
+    assert_eq!(times(&matches), times(&loaded_matches));
+    assert_eq!(names(&teams), names(&loaded_teams));
+    assert_eq!(events.len(), loaded_events.len());
+    for (a, b) in events.iter().zip(&loaded_events) {
+        assert_eq!((a.hltv_id, a.last_match_time, a.prize_pool), (b.hltv_id, b.last_match_time, b.prize_pool));
+    }
+}
+
+#[test]
+fn windows_do_not_leak_into_each_other() {
+    let dataset = Dataset::load(&feed()).unwrap();
+    assert_eq!(dataset.matches_read(), 4);
+    assert_eq!(dataset.latest_match_time().unwrap(), WINDOW_START + 101 * DAY);
+
+    // Only the early matches, then everything
+    let early = RankingContext { time_window_end: WINDOW_START + 50 * DAY, ..context() };
+    let (matches, events, teams) = dataset.window(&early).unwrap();
+    assert_eq!(matches.len(), 2);
+    assert_eq!(names(&teams), vec!["Alpha", "Bravo"]);
+    assert!(events.iter().find(|ev| ev.hltv_id == 8).unwrap().prize_distribution.iter().all(|pd| !pd.is_in_ranking()));
+
+    let (matches, events, teams) = dataset.window(&context()).unwrap();
+    assert_eq!(matches.len(), 4);
+    assert_eq!(teams.len(), 3);
+    assert!(events.iter().all(|ev| ev.prize_distribution.iter().all(|pd| pd.is_in_ranking())));
+
+    // Information context is worked out per window, not left over from the last one
+    let (matches, _, _) = dataset.window(&early).unwrap();
+    let (reloaded, _, _) = load_data(dataset.file_path.clone(), &early).unwrap();
+    for (a, b) in matches.iter().zip(&reloaded) {
+        assert_eq!(a.information_context, b.information_context);
+    }
+}
+
+#[test]
+fn ranking_a_window_twice_gives_the_same_result() {
+    let dataset = Dataset::load(&feed()).unwrap();
+    let ranking_context = RankingContext { time_grace_period: 0, ..context() };
+
+    let mut elos = Vec::new();
+    for _ in 0..2 {
+        let (matches, events, mut teams) = dataset.window(&ranking_context).unwrap();
+        gen_rank_new(&matches, &events, &mut teams, &ranking_context).unwrap();
+        elos.push(teams.iter().map(|t| t.elo).collect::<Vec<_>>());
+    }
+
+    assert_eq!(elos[0], elos[1]);
+}
+
+#[test]
+fn window_checks_the_context_and_load_checks_the_file() {
+    let dataset = Dataset::load(&feed()).unwrap();
+    let backwards = RankingContext { time_window_start: WINDOW_END, time_window_end: WINDOW_START, ..context() };
+    assert!(matches!(dataset.window(&backwards), Err(Error::Context(_))));
+
+    assert!(matches!(Dataset::load("/nonexistent/matchdata.json"), Err(Error::Io { .. })));
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use alternative_vrs::*;
+use common::*;
+
+const SEED: f64 = 1500.0;
+
+fn teams() -> Vec<Team> {
+    vec![team(0, "A", 1, SEED), team(1, "B", 11, SEED + 100.0), team(2, "C", 21, SEED - 100.0)]
+}
+
+// B beats A 2-0, C beats A, then B beats C. Passed newest first, to check the ledger is in replay order.
+fn matches() -> Vec<Match> {
+    vec![
+        indexed_match(WINDOW_START + 3 * DAY, 1, 2, 1, 1),
+        indexed_match(WINDOW_START + 2 * DAY, 2, 0, 2, 1),
+        indexed_match(WINDOW_START + DAY, 0, 1, 1, 2),
+    ]
+}
+
+#[test]
+fn ledger_does_not_change_the_result() {
+    let mut without = teams();
+    map_elo_adjustments(&matches(), &mut without, &context()).unwrap();
+
+    let mut with = teams();
+    let ledger = map_elo_adjustments_with_ledger(&matches(), &mut with, &context()).unwrap();
+
+    assert_eq!(ledger.entries.len(), 4);
+    for (a, b) in without.iter().zip(&with) {
+        assert_eq!(a.elo, b.elo);
+    }
+}
+
+#[test]
+fn entries_are_in_replay_order_and_chain_up() {
+    let seeds = teams();
+    let mut ranked = teams();
+    let ledger = map_elo_adjustments_with_ledger(&matches(), &mut ranked, &context()).unwrap();
+
+    let times: Vec<u32> = ledger.entries.iter().map(|e| e.match_start_time).collect();
+    assert!(times.windows(2).all(|pair| pair[0] <= pair[1]));
+
+    // Each team's history starts at its seed and ends at its final rating
+    for (seed, t) in seeds.iter().zip(&ranked) {
+        let mut elo = seed.elo;
+        for e in ledger.entries_for(t.id) {
+            let (before, change) = e.for_team(t.id).unwrap();
+            assert_eq!(before, elo);
+            elo += change;
+        }
+        assert!((elo - t.elo).abs() < 1e-9);
+        assert_eq!(ledger.rating_history(t.id).last().unwrap().1, elo);
+    }
+}
+
+#[test]
+fn delta_follows_the_win_probability() {
+    let ranking_context = context();
+    let mut teams = teams();
+    let ledger = map_elo_adjustments_with_ledger(&matches(), &mut teams, &ranking_context).unwrap();
+
+    let first = &ledger.entries[0];
+    assert_eq!((first.team_1, first.team_2), (TeamId(0), TeamId(1)));
+    assert_eq!((first.team_1_elo_before, first.team_2_elo_before), (SEED, SEED + 100.0));
+    assert!((first.team_1_win_probability - win_probability(SEED, SEED + 100.0, &ranking_context)).abs() < 1e-12);
+    assert!(first.team_1_win_probability < 0.5);
+
+    // Team 2 won, so the delta is negative and as big as the favourite's gain
+    assert!(first.delta < 0.0);
+    assert!((-first.delta - ranking_context.elo_k * first.team_1_win_probability).abs() < 1e-9);
+    assert_eq!((first.team_1_score, first.team_2_score), (7, 13));
+}
+
+#[test]
+fn ledger_exports_to_csv() {
+    let mut teams = teams();
+    let ledger = map_elo_adjustments_with_ledger(&matches(), &mut teams, &context()).unwrap();
+
+    let mut buffer = Vec::new();
+    ledger.write_csv(&mut buffer).unwrap();
+
+    let mut reader = csv::Reader::from_reader(&buffer[..]);
+    let headers = reader.headers().unwrap().clone();
+    let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+
+    assert_eq!(rows.len(), ledger.entries.len());
+    let column = |name: &str| headers.iter().position(|h| h == name).unwrap();
+    assert_eq!(&rows[0][column("map_name")], "de_nuke");
+    assert_eq!(&rows[0][column("date")], "2023-03-03");
+    assert_eq!(rows[3][column("delta")].parse::<f64>().unwrap(), ledger.entries[3].delta);
+}
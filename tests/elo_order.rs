@@ -0,0 +1,87 @@
+mod common;
+
+use alternative_vrs::*;
+use common::*;
+
+const SEED: f64 = 1500.0;
+
+// Runs the Head-to-Head adjustment for two teams that start on the same seed
+fn replay(matches: &[Match]) -> Vec<Team> {
+    let mut teams = vec![team(0, "A", 1, SEED), team(1, "B", 11, SEED)];
+    map_elo_adjustments(matches, &mut teams, &context()).unwrap();
+    teams
+}
+
+#[test]
+fn matches_are_replayed_oldest_first_regardless_of_input_order() {
+    let older = indexed_match(WINDOW_START + DAY, 0, 1, 1, 1);
+    let newer = indexed_match(WINDOW_START + 2 * DAY, 0, 1, 0, 1);
+
+    let forwards = replay(&[older.clone(), newer.clone()]);
+    let backwards = replay(&[newer, older]);
+
+    assert_eq!(forwards[0].elo, backwards[0].elo);
+    assert_eq!(forwards[1].elo, backwards[1].elo);
+
+    // B won first, so A was the underdog when it won the newer match and gained more than it lost
+    let ranking_context = context();
+    let lost = elo_result(SEED, SEED, &ranking_context);
+    let won = elo_result(SEED - lost, SEED + lost, &ranking_context);
+    assert!((forwards[0].elo - (SEED - lost + won)).abs() < 1e-9);
+    assert!(forwards[0].elo > SEED);
+}
+
+#[test]
+fn maps_are_replayed_in_listed_order() {
+    // A takes the first map, B the second
+    let mut m = indexed_match(WINDOW_START + DAY, 0, 1, 0, 2);
+    m.maps[1] = Map { map_name: "de_inferno".to_string(), team_1_score: 4, team_2_score: 13 };
+
+    let teams = replay(&[m]);
+
+    let ranking_context = context();
+    let first = elo_result(SEED, SEED, &ranking_context);
+    let second = elo_result(SEED - first, SEED + first, &ranking_context);
+    assert!((teams[0].elo - (SEED + first - second)).abs() < 1e-9);
+}
+
+#[test]
+fn adding_an_older_match_only_nudges_a_newer_one() {
+    let ranking_context = context();
+    let newer = indexed_match(WINDOW_START + 10 * DAY, 0, 1, 0, 1);
+
+    let without = replay(std::slice::from_ref(&newer));
+    let delta_without = without[0].elo - SEED;
+
+    // B beats A long before the newer match
+    let older = indexed_match(WINDOW_START + DAY, 0, 1, 1, 1);
+    let after_older = replay(std::slice::from_ref(&older));
+    let with = replay(&[newer, older]);
+    let delta_with = with[0].elo - after_older[0].elo;
+
+    // The older match can only affect the newer one through the ratings going into it. The slope of the Elo curve is
+    // at most ln(10) / (4 * elo_delta) per point of rating difference, so that bounds how far the newer delta can move.
+    let swing = (after_older[1].elo - after_older[0].elo).abs();
+    let bound = ranking_context.elo_k * swing * 10f64.ln() / (4.0 * ranking_context.elo_delta);
+
+    assert!(delta_with > delta_without);
+    assert!(delta_with - delta_without <= bound + 1e-9);
+}
+
+#[test]
+fn load_data_returns_matches_oldest_first() {
+    let a = (101, "Alpha", 1);
+    let b = (102, "Bravo", 11);
+
+    let path = Feed::default()
+        .event(1, &[])
+        .match_(WINDOW_START + 3 * DAY, 1, a, b, 1)
+        .match_(WINDOW_START + DAY, 1, a, b, 2)
+        .match_(WINDOW_START + 2 * DAY, 1, b, a, 1)
+        .write();
+
+    let (matches, _, _) = load_data(path, &context()).unwrap();
+    let times: Vec<u32> = matches.iter().map(|m| m.match_start_time).collect();
+
+    assert_eq!(times, vec![WINDOW_START + DAY, WINDOW_START + 2 * DAY, WINDOW_START + 3 * DAY]);
+}
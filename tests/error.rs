@@ -0,0 +1,67 @@
+mod common;
+
+use alternative_vrs::*;
+use alternative_vrs::validation::{Issue, Record, Severity, ValidationIssue};
+use common::*;
+
+#[test]
+fn schema_errors_point_at_the_field() {
+    let path = temp_file("json", r#"{ "matches": [{ "matchStartTime": "soon" }], "events": [] }"#);
+
+    match Dataset::load(&path) {
+        Err(Error::Json { path: error_path, field, .. }) => {
+            assert_eq!(error_path, path);
+            assert!(field.starts_with("matches[0]"), "{field}");
+        }
+        other => panic!("expected a JSON error, got {other:?}"),
+    }
+}
+
+#[test]
+fn a_match_at_an_unknown_event_is_refused() {
+    let path = Feed::default().event(7, &[]).match_(WINDOW_START + DAY, 9, ALPHA, BRAVO, 1).write();
+
+    let result = load_data(path, &context());
+    assert!(matches!(result, Err(Error::UnknownEvent { event_id: 9, ref team_1_name, .. }) if team_1_name == "Alpha"));
+}
+
+#[test]
+fn prize_money_for_a_team_without_matches_is_skipped() {
+    let path = Feed::default()
+        .event(7, &[(1, 999, 1000.0, false), (2, ALPHA.0, 500.0, false)])
+        .match_(WINDOW_START + DAY, 7, ALPHA, BRAVO, 1)
+        .write();
+
+    let (_, events, _) = load_data(path, &context()).unwrap();
+    let teams: Vec<Option<TeamId>> = events[0].prize_distribution.iter().map(|pd| pd.team).collect();
+    assert!(teams[0].is_none() && teams[1].is_some());
+}
+
+#[test]
+fn other_event_errors_are_not_numeric() {
+    let record = Record::Event { index: 0, event_id: 7, event_name: "Event 7".to_string() };
+    let report = ValidationReport {
+        issues: vec![ValidationIssue { severity: Severity::Error, record, issue: Issue::DuplicateEvent }],
+        ..Default::default()
+    };
+
+    assert!(matches!(report.first_error(), Some(Error::InvalidEvent { event_id: 7, .. })));
+    assert!(report.to_json().unwrap().contains("\"duplicate_event\""));
+}
+
+#[test]
+fn a_core_needs_five_players() {
+    let mut matcher = CoreMatching::default().matcher();
+    let result = insert_team(&mut Vec::new(), "Alpha", &lineup(1)[..3], matcher.as_mut());
+
+    assert!(matches!(result, Err(Error::IncompleteLineup { players: 3, .. })));
+}
+
+#[test]
+fn too_few_teams_to_scale_by_is_numeric() {
+    let ranking_context = RankingContext { top_outlier_count: 5, ..context() };
+    let (matches, events, mut teams) = round_robin_dataset().window(&ranking_context).unwrap();
+
+    let result = gen_rank_new(&matches, &events, &mut teams, &ranking_context);
+    assert!(matches!(result, Err(Error::Numeric(ref message)) if message.contains("5th best team")));
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use alternative_vrs::*;
+use common::*;
+
+fn prediction(probability: f64, outcome: bool) -> Prediction {
+    Prediction { match_start_time: 0, probability, outcome }
+}
+
+#[test]
+fn coin_flips_score_as_expected() {
+    let predictions = [prediction(0.5, true), prediction(0.5, false)];
+    let scores = score(&predictions, 10);
+
+    assert_eq!(scores.predictions, 2);
+    assert!((scores.log_loss - 2f64.ln()).abs() < 1e-12);
+    assert!((scores.brier - 0.25).abs() < 1e-12);
+    assert_eq!(scores.calibration_error, 0.0);
+}
+
+#[test]
+fn confident_misses_cost_more_than_hedged_ones() {
+    let confident = score(&[prediction(0.99, false)], 10);
+    let hedged = score(&[prediction(0.6, false)], 10);
+    assert!(confident.log_loss > hedged.log_loss);
+    assert!(confident.brier > hedged.brier);
+
+    // Certain and wrong is capped rather than infinite
+    assert!(score(&[prediction(1.0, false)], 10).log_loss.is_finite());
+}
+
+#[test]
+fn buckets_split_predictions_by_probability() {
+    let predictions = [prediction(0.05, false), prediction(0.72, true), prediction(0.78, false), prediction(1.0, true)];
+    let scores = score(&predictions, 5);
+
+    assert_eq!(scores.buckets.len(), 5);
+    let counts: Vec<usize> = scores.buckets.iter().map(|b| b.predictions).collect();
+    assert_eq!(counts, vec![1, 0, 0, 2, 1]);
+
+    let third = &scores.buckets[3];
+    assert_eq!((third.from, third.to), (0.6, 0.8));
+    assert!((third.expected - 0.75).abs() < 1e-12);
+    assert_eq!(third.observed, 0.5);
+
+    // |0.05 - 0| + |1.5 - 1| + |1 - 1|, over four predictions
+    assert!((scores.calibration_error - 0.55 / 4.0).abs() < 1e-12);
+
+    assert_eq!(score(&[], 5).predictions, 0);
+}
+
+#[test]
+fn accuracy_and_auc() {
+    // Three of four favourites won, and the one upset came from the least confident prediction
+    let predictions = [prediction(0.9, true), prediction(0.7, true), prediction(0.6, false), prediction(0.2, false), prediction(0.5, true)];
+    let scores = score(&predictions, 10);
+
+    assert!((scores.accuracy - 3.5 / 5.0).abs() < 1e-12);
+    // Wins at 0.9, 0.7 and 0.5 against losses at 0.6 and 0.2: five of six pairs ranked right
+    assert!((scores.auc.unwrap() - 5.0 / 6.0).abs() < 1e-12);
+
+    // Ties count as half
+    assert_eq!(score(&[prediction(0.4, true), prediction(0.4, false)], 10).auc, Some(0.5));
+    assert_eq!(score(&[prediction(0.4, true)], 10).auc, None);
+}
+
+#[test]
+fn wilson_intervals_narrow_with_more_predictions() {
+    let (low, high) = wilson_interval(7, 10);
+    assert!(low < 0.7 && 0.7 < high);
+    assert!((low - 0.3968).abs() < 1e-4 && (high - 0.8922).abs() < 1e-4);
+
+    let (wide_low, wide_high) = wilson_interval(1, 2);
+    let (narrow_low, narrow_high) = wilson_interval(500, 1000);
+    assert!(narrow_high - narrow_low < wide_high - wide_low);
+
+    assert_eq!(wilson_interval(0, 0), (0.0, 1.0));
+    assert_eq!(wilson_interval(0, 5).0, 0.0);
+    assert_eq!(wilson_interval(5, 5).1, 1.0);
+}
+
+#[test]
+fn maps_are_scored_on_their_own() {
+    let teams = vec![team(0, "A", 1, 1600.0), team(1, "B", 11, 1400.0)];
+
+    // A wins 2-1, then loses 0-1
+    let mut first = indexed_match(WINDOW_START, 0, 1, 0, 0);
+    first.maps = vec![map(13, 5), map(8, 13), map(16, 14)];
+    let second = indexed_match(WINDOW_START + DAY, 0, 1, 1, 1);
+    let matches = vec![first, second];
+
+    let ranking_context = RankingContext::default();
+    let per_match = match_predictions(&teams, &matches, &ranking_context, &AlternativeVrs).unwrap();
+    let per_map = map_predictions(&teams, &matches, &ranking_context, &AlternativeVrs).unwrap();
+    assert_eq!(per_match.iter().map(|p| p.outcome).collect::<Vec<_>>(), vec![true, false]);
+    assert_eq!(per_map.iter().map(|p| p.outcome).collect::<Vec<_>>(), vec![true, false, true, false]);
+    assert!(per_map.iter().all(|p| p.probability == win_probability(1600.0, 1400.0, &ranking_context)));
+
+    let evaluation = evaluate(&teams, &matches, &ranking_context, &AlternativeVrs, 10).unwrap();
+    assert_eq!((evaluation.matches.predictions, evaluation.maps.predictions), (2, 4));
+    assert_eq!(evaluation.maps.accuracy, 0.5);
+}
+
+fn map(team_1_score: u16, team_2_score: u16) -> Map {
+    Map { map_name: "de_inferno".to_string(), team_1_score, team_2_score }
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use alternative_vrs::*;
+use common::*;
+
+fn feed() -> String {
+    Feed::default()
+        .event(7, &[(1, ALPHA.0, 10000.0, false), (2, BRAVO.0, 5000.0, false)])
+        .event(8, &[(1, BRAVO.0, 3000.0, false), (2, ALPHA.0, 1000.0, false), (3, CHARLIE.0, 400.0, false)])
+        .match_(WINDOW_START + 10 * DAY, 7, ALPHA, BRAVO, 1)
+        .match_(WINDOW_START + 11 * DAY, 7, BRAVO, CHARLIE, 1)
+        .match_(WINDOW_START + 12 * DAY, 7, CHARLIE, ALPHA, 2)
+        .match_(WINDOW_START + 90 * DAY, 8, ALPHA, CHARLIE, 1)
+        .match_(WINDOW_START + 91 * DAY, 8, BRAVO, ALPHA, 1)
+        .match_(WINDOW_START + 92 * DAY, 8, CHARLIE, BRAVO, 2)
+        .write()
+}
+
+fn ranked() -> (Vec<Match>, Vec<Event>, Vec<Team>) {
+    let (matches, events, mut teams) = load_data(feed(), &context()).unwrap();
+    gen_rank_new(&matches, &events, &mut teams, &context()).unwrap();
+    (matches, events, teams)
+}
+
+fn alpha(teams: &[Team]) -> TeamId {
+    teams.iter().find(|t| t.name == "Alpha").unwrap().id
+}
+
+#[test]
+fn explanation_adds_up_to_the_ranking() {
+    let (matches, events, teams) = ranked();
+    let id = alpha(&teams);
+    let team = &teams[id.0];
+
+    let explanation = explain_team(&matches, &events, &teams, &context(), id).unwrap();
+
+    assert_eq!(explanation.name, "Alpha");
+    assert_eq!(explanation.prize_money.value, team.prize_money);
+    assert_eq!(explanation.event_participation.value, team.event_participation);
+    assert_eq!(explanation.opponent_winnings.value, team.opponent_winnings);
+    assert_eq!(explanation.opponent_network.value, team.opponent_network);
+    assert_eq!(explanation.seed.seed_points, team.seed_points);
+    assert_eq!(explanation.elo, team.elo);
+
+    let weighted: f64 = explanation.seed.factors.iter().map(|(_, value, weight)| value * weight).sum();
+    assert!((weighted - explanation.seed.sum_of_factors).abs() < 1e-9);
+}
+
+#[test]
+fn events_are_listed_with_their_contribution() {
+    let (matches, events, teams) = ranked();
+    let explanation = explain_team(&matches, &events, &teams, &context(), alpha(&teams)).unwrap();
+
+    let placements: Vec<(u32, f64)> = explanation.events.iter().map(|e| (e.placement, e.prize)).collect();
+    assert_eq!(placements, vec![(1, 10000.0), (2, 1000.0)]);
+
+    // The later event counts for more
+    assert!(explanation.events[1].time_decay > explanation.events[0].time_decay);
+
+    let prize_money: f64 = explanation.events.iter().map(|e| e.prize_money).sum();
+    assert!((prize_money - explanation.prize_money.sum).abs() < 1e-9);
+    assert!((explanation.events[0].prize_money - 100.0 * explanation.events[0].time_decay).abs() < 1e-9);
+}
+
+#[test]
+fn counted_opponents_are_the_best_wins() {
+    let (matches, events, teams) = ranked();
+    let ranking_context = RankingContext { factor_bucket_size: 2, ..context() };
+    let explanation = explain_team(&matches, &events, &teams, &ranking_context, alpha(&teams)).unwrap();
+
+    // Alpha won three matches, only the best two count
+    let counted = &explanation.opponent_winnings.counted;
+    assert_eq!(counted.len(), 2);
+    assert!(counted[0].value >= counted[1].value);
+    assert!((counted.iter().map(|c| c.value).sum::<f64>() - explanation.opponent_winnings.sum).abs() < 1e-9);
+}
+
+#[test]
+fn maps_are_the_team_elo_history() {
+    let (matches, events, teams) = ranked();
+    let explanation = explain_team(&matches, &events, &teams, &context(), alpha(&teams)).unwrap();
+
+    assert_eq!(explanation.maps.len(), 4);
+    assert_eq!(explanation.maps[0].elo_before, explanation.seed.seed_points);
+    for pair in explanation.maps.windows(2) {
+        assert_eq!(pair[0].elo_after, pair[1].elo_before);
+    }
+    assert_eq!(explanation.maps.last().unwrap().elo_after, explanation.elo);
+
+    // The first map is Alpha's 13-5 win over Bravo
+    assert_eq!(explanation.maps[0].score, (13, 5));
+    assert!(explanation.maps[0].change > 0.0);
+    assert_eq!(explanation.maps[0].opponent_name, "Bravo");
+}
+
+#[test]
+fn ranking_twice_gives_the_same_result() {
+    let (matches, events, mut teams) = ranked();
+    let first: Vec<f64> = teams.iter().map(|t| t.elo).collect();
+
+    gen_rank_new(&matches, &events, &mut teams, &context()).unwrap();
+    assert_eq!(teams.iter().map(|t| t.elo).collect::<Vec<_>>(), first);
+}
+
+#[test]
+fn other_rating_systems_are_refused() {
+    let (matches, events, teams) = ranked();
+    let ranking_context = RankingContext { rating_system: RatingSystemKind::OriginalVrs, ..context() };
+
+    let result = explain_team(&matches, &events, &teams, &ranking_context, alpha(&teams));
+    assert!(matches!(result, Err(Error::UnsupportedRatingSystem { system: RatingSystemKind::OriginalVrs, .. })));
+
+    let mut ranked = teams.clone();
+    let result = gen_rank_with_ledger(&matches, &events, &mut ranked, &ranking_context);
+    assert!(matches!(result, Err(Error::UnsupportedRatingSystem { system: RatingSystemKind::OriginalVrs, .. })));
+}
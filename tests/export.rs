@@ -0,0 +1,106 @@
+mod common;
+
+use alternative_vrs::*;
+use common::*;
+
+// Charlie never wins, so it isn't ranking eligible
+fn standings() -> Standings {
+    let path = Feed::default()
+        .event(7, &[(1, ALPHA.0, 1000.0, false), (2, BRAVO.0, 500.0, false)])
+        .match_(WINDOW_START + DAY, 7, ALPHA, BRAVO, 1)
+        .match_(WINDOW_START + 2 * DAY, 7, BRAVO, CHARLIE, 1)
+        .match_(WINDOW_START + 3 * DAY, 7, CHARLIE, ALPHA, 2)
+        .write();
+
+    let ranking_context = RankingContext { min_wins_for_ranking: 1, ..context() };
+    let (matches, events, mut teams) = load_data(path.clone(), &ranking_context).unwrap();
+    gen_rank_new(&matches, &events, &mut teams, &ranking_context).unwrap();
+
+    Standings::new(&teams, &ranking_context, &path)
+}
+
+#[test]
+fn standings_are_sorted_and_only_eligible_teams_are_ranked() {
+    let standings = standings();
+    let rows = &standings.standings;
+
+    assert_eq!(rows.len(), 3);
+    assert!(rows.windows(2).all(|pair| pair[0].elo >= pair[1].elo));
+
+    let charlie = rows.iter().find(|r| r.name == "Charlie").unwrap();
+    assert!(!charlie.eligible);
+    assert_eq!(charlie.rank, None);
+
+    let ranks: Vec<usize> = rows.iter().filter_map(|r| r.rank).collect();
+    assert_eq!(ranks, vec![1, 2]);
+    assert_eq!(rows[0].core, vec!["player1", "player2", "player3", "player4", "player5"]);
+}
+
+#[test]
+fn json_has_the_context_and_window() {
+    let standings = standings();
+
+    let mut buffer = Vec::new();
+    standings.write_json(&mut buffer).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+
+    assert_eq!(json["metadata"]["window_end"], "2023-08-29");
+    assert_eq!(json["metadata"]["ranking_context"]["time_window_start"], WINDOW_START);
+    assert_eq!(json["standings"].as_array().unwrap().len(), 3);
+    assert_eq!(json["standings"][0]["rank"], 1);
+
+    // The context in the metadata is a valid config file
+    let ranking_context: RankingContext = serde_json::from_value(json["metadata"]["ranking_context"].clone()).unwrap();
+    assert_eq!(ranking_context.time_window_end, WINDOW_END);
+}
+
+#[test]
+fn csv_has_a_header_and_a_row_per_team() {
+    let standings = standings();
+
+    let mut buffer = Vec::new();
+    standings.write_csv(&mut buffer).unwrap();
+    let text = String::from_utf8(buffer).unwrap();
+    assert!(text.starts_with("rank,team_id,name,"));
+
+    let mut reader = csv::Reader::from_reader(text.as_bytes());
+    let headers = reader.headers().unwrap().clone();
+    let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+
+    assert_eq!(rows.len(), 3);
+    for field in ["rank", "elo", "seed_points", "own_network", "matches_won", "eligible", "core_5"] {
+        assert!(headers.iter().any(|h| h == field), "missing {field}");
+    }
+
+    let column = |name: &str| headers.iter().position(|h| h == name).unwrap();
+    assert_eq!(&rows[0][column("rank")], "1");
+    assert_eq!(&rows[2][column("rank")], "");
+    assert_eq!(&rows[2][column("eligible")], "false");
+    assert_eq!(rows[0][column("elo")].parse::<f64>().unwrap(), standings.standings[0].elo);
+}
+
+#[test]
+fn csv_export_writes_the_metadata_next_to_it() {
+    let standings = standings();
+    let path = temp_file("csv", "");
+    export_standings(&standings, &path, ExportFormat::Csv).unwrap();
+
+    let csv = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(csv.lines().count(), 4);
+
+    let meta_path = metadata_path(&path);
+    assert!(meta_path.ends_with(".meta.json"));
+    let metadata: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(meta_path).unwrap()).unwrap();
+    assert_eq!(metadata["window_start"], "2023-03-02");
+    assert_eq!(metadata["window_end"], "2023-08-29");
+    assert_eq!(metadata["ranking_context"]["time_window_end"], WINDOW_END);
+    assert_eq!(metadata["version"], env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn export_reports_the_path_it_could_not_write() {
+    let standings = standings();
+    let result = export_standings(&standings, "/nonexistent/standings.csv", ExportFormat::Csv);
+
+    assert!(matches!(result, Err(Error::Write { ref path, .. }) if path == "/nonexistent/standings.csv"));
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use alternative_vrs::*;
+use common::*;
+
+fn glicko2() -> RatingSystemKind {
+    RatingSystemKind::Glicko2 { tau: 0.5, volatility: 0.06, max_deviation: 350.0, min_deviation: 50.0 }
+}
+
+fn ranked(days: u32) -> (Vec<Team>, RankingContext) {
+    let ranking_context = RankingContext { time_grace_period: 0, rating_system: glicko2(), ..context() };
+    let (matches, events, mut teams) = Dataset::load(&round_robin_feed(days).write()).unwrap().window(&ranking_context).unwrap();
+    rank(&matches, &events, &mut teams, &ranking_context).unwrap();
+    (teams, ranking_context)
+}
+
+fn by_name<'a>(teams: &'a [Team], name: &str) -> &'a Team {
+    teams.iter().find(|t| t.name == name).unwrap()
+}
+
+// Glickman's example player, 1500 with a deviation of 200, beating the 1400 rated opponent from the same example.
+// Expected values worked out by hand from the paper's steps
+#[test]
+fn a_single_map_follows_the_paper() {
+    let mut teams = vec![team(0, "Alpha", 1, 1500.0), team(1, "Bravo", 11, 1400.0)];
+    teams[0].rating_deviation = Some(200.0);
+    teams[1].rating_deviation = Some(30.0);
+
+    let m = indexed_match(WINDOW_START, 0, 1, 0, 1);
+    let mut system = Glicko2::new(0.5, 0.06, 350.0, 50.0);
+    system.update_map(&m, &m.maps[0], &mut teams, &RankingContext::default());
+
+    assert!((teams[0].elo - 1563.5641948).abs() < 1e-5);
+    assert!((teams[0].rating_deviation.unwrap() - 175.4026544).abs() < 1e-5);
+    assert!((teams[1].elo - 1398.1435582).abs() < 1e-5);
+
+    // The opponent was already well known, so it barely moves, and its deviation grows with its volatility, up to
+    // the lowest a deviation can be
+    assert_eq!(teams[1].rating_deviation, Some(50.0));
+}
+
+#[test]
+fn deviations_start_at_the_maximum_and_shrink_with_every_map() {
+    // Seeding doesn't count the matches, the replay does
+    let ranking_context = RankingContext { time_grace_period: 0, rating_system: glicko2(), ..context() };
+    let (matches, events, mut teams) = round_robin_dataset().window(&ranking_context).unwrap();
+    Glicko2::new(0.5, 0.06, 350.0, 50.0).seed(&matches, &events, &mut teams, &ranking_context).unwrap();
+    assert!(teams.iter().all(|t| t.rating_deviation == Some(350.0)));
+
+    // Alpha and Bravo split their maps, so more of them pins both down. Charlie loses every map by so much that they
+    // teach next to nothing, but they still bring it down from the maximum
+    let (few, _) = ranked(2);
+    let (many, _) = ranked(8);
+    for name in ["Alpha", "Bravo"] {
+        let (few, many) = (by_name(&few, name), by_name(&many, name));
+        assert!(many.rating_deviation.unwrap() < few.rating_deviation.unwrap());
+    }
+    assert!(many.iter().all(|t| t.rating_deviation.unwrap() < 350.0));
+
+    // Alpha beats Bravo, Bravo beats Charlie and Charlie loses to Alpha every day
+    assert!(by_name(&many, "Alpha").elo > by_name(&many, "Bravo").elo);
+    assert!(by_name(&many, "Bravo").elo > by_name(&many, "Charlie").elo);
+}
+
+#[test]
+fn uncertain_ratings_predict_closer_to_a_coin_flip() {
+    let ranking_context = RankingContext::default();
+    let system = Glicko2::new(0.5, 0.06, 350.0, 50.0);
+
+    let mut strong = team(0, "Alpha", 1, 1700.0);
+    let mut weak = team(1, "Bravo", 11, 1500.0);
+    let certain = system.predict(&strong, &weak, &ranking_context);
+    assert!((certain - win_probability(1700.0, 1500.0, &ranking_context)).abs() < 1e-12);
+
+    strong.rating_deviation = Some(300.0);
+    weak.rating_deviation = Some(300.0);
+    let uncertain = system.predict(&strong, &weak, &ranking_context);
+    assert!(uncertain > 0.5 && uncertain < certain);
+    assert!((uncertain + system.predict(&weak, &strong, &ranking_context) - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn eligibility_and_reports_use_the_deviation() {
+    let (teams, mut ranking_context) = ranked(4);
+    ranking_context.min_matches_for_ranking = 1000;
+    assert!(teams.iter().all(|t| !ranking_eligible(t, &ranking_context)));
+
+    ranking_context.max_deviation_for_ranking = Some(by_name(&teams, "Bravo").rating_deviation.unwrap());
+    let eligible: Vec<&str> = teams.iter().filter(|t| ranking_eligible(t, &ranking_context)).map(|t| t.name.as_str()).collect();
+    assert!(eligible.contains(&"Bravo"));
+
+    let alpha = by_name(&teams, "Alpha");
+    ranking_context.max_deviation_for_ranking = Some(1000.0);
+    let report = markdown_report(&teams, &ranking_context);
+    assert!(report.contains(&format!("{:.1} ± {:.1}", alpha.elo, 1.96 * alpha.rating_deviation.unwrap())));
+    assert!(report.contains("Rating system: Glicko-2"));
+
+    let standings = Standings::new(&teams, &ranking_context, "feed.json");
+    assert!(standings.standings.iter().all(|row| row.rating_deviation.is_some()));
+}
+
+#[test]
+fn settings_are_validated() {
+    let valid = RankingContext { rating_system: glicko2(), ..Default::default() };
+    assert!(valid.validate().is_ok());
+
+    let invalid = RankingContext {
+        rating_system: RatingSystemKind::Glicko2 { tau: 0.5, volatility: 0.06, max_deviation: 50.0, min_deviation: 350.0 },
+        ..Default::default()
+    };
+    assert!(matches!(invalid.validate(), Err(ContextError::InvalidRatingSystem(_))));
+}
+
+#[test]
+fn max_deviation_for_ranking_is_validated() {
+    let valid = RankingContext { max_deviation_for_ranking: Some(80.0), ..Default::default() };
+    assert!(valid.validate().is_ok());
+
+    for value in [0.0, -80.0, f64::NAN, f64::INFINITY] {
+        let invalid = RankingContext { max_deviation_for_ranking: Some(value), ..Default::default() };
+        assert!(matches!(invalid.validate(), Err(ContextError::InvalidMaxDeviation(_))), "{value}");
+    }
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use alternative_vrs::*;
+use alternative_vrs::util::{format_date, parse_date};
+use common::*;
+
+#[test]
+fn cutoffs_step_from_the_first_weekday() {
+    let from = parse_date("2023-08-02").unwrap() + 5000;
+    let to = parse_date("2023-08-29").unwrap();
+
+    let daily: Vec<String> = cutoffs(from, to, 7, None).into_iter().map(format_date).collect();
+    assert_eq!(daily, vec!["2023-08-02", "2023-08-09", "2023-08-16", "2023-08-23"]);
+
+    // 2023-08-07 was a Monday
+    let mondays: Vec<String> = cutoffs(from, to, 7, Some(Weekday::Monday)).into_iter().map(format_date).collect();
+    assert_eq!(mondays, vec!["2023-08-07", "2023-08-14", "2023-08-21", "2023-08-28"]);
+
+    let wednesdays = cutoffs(from, to, 14, Some(Weekday::Wednesday));
+    assert_eq!(wednesdays.first().copied().map(format_date).as_deref(), Some("2023-08-02"));
+    assert_eq!(wednesdays.len(), 2);
+}
+
+#[test]
+fn history_reranks_at_every_cutoff() {
+    // Alpha wins early on, Bravo wins everything later
+    let mut feed = Feed::default().event(7, &[]);
+    for day in 1..=3 {
+        feed = feed.match_(WINDOW_START + day * DAY, 7, ALPHA, BRAVO, 1);
+    }
+    for day in 20..=30 {
+        feed = feed.match_(WINDOW_START + day * DAY, 7, ALPHA, BRAVO, 2);
+    }
+    let path = feed.write();
+
+    let ranking_context = RankingContext { time_grace_period: 0, ..context() };
+    let cutoffs = [WINDOW_START, WINDOW_START + 10 * DAY, WINDOW_START + 40 * DAY];
+    let history = standings_history(&Dataset::load(&path).unwrap(), &ranking_context, &cutoffs, 60).unwrap();
+
+    // Nobody has played at the first cutoff
+    assert_eq!(history.skipped.len(), 1);
+    assert_eq!(history.skipped[0].0, WINDOW_START);
+
+    let leaders: Vec<(u32, &str)> = history.points.iter().filter(|p| p.rank == 1).map(|p| (p.cutoff, p.name.as_str())).collect();
+    assert_eq!(leaders, vec![(cutoffs[1], "Alpha"), (cutoffs[2], "Bravo")]);
+
+    let alpha = history.team("Alpha");
+    assert_eq!(alpha.len(), 2);
+    assert_eq!(alpha[0].matches_played, 3);
+    assert_eq!(alpha[1].matches_played, 14);
+    assert_eq!(alpha[1].hltv_ids, vec![ALPHA.0]);
+}
+
+#[test]
+fn windows_the_context_refuses_are_skipped() {
+    let dataset = round_robin_dataset();
+    let ranking_context = RankingContext { time_grace_period: 30 * DAY, ..context() };
+
+    // The first window is cut off at the beginning of time, leaving less than the grace period
+    let cutoffs = [20 * DAY, WINDOW_START + 10 * DAY];
+    let history = standings_history(&dataset, &ranking_context, &cutoffs, 60).unwrap();
+    assert_eq!(history.skipped.len(), 1);
+    assert_eq!(history.skipped[0].0, 20 * DAY);
+    assert!(history.skipped[0].1.contains("time_grace_period"));
+    assert!(!history.points.is_empty() && history.points.iter().all(|p| p.cutoff == cutoffs[1]));
+
+    // A window shorter than the grace period skips every cutoff
+    let history = standings_history(&dataset, &ranking_context, &cutoffs, 20).unwrap();
+    assert_eq!(history.skipped.len(), 2);
+    assert!(history.points.is_empty());
+}
+
+#[test]
+fn history_exports_in_long_format() {
+    let path = Feed::default()
+        .event(7, &[])
+        .match_(WINDOW_START + DAY, 7, ALPHA, BRAVO, 1)
+        .write();
+
+    let ranking_context = RankingContext { time_grace_period: 0, ..context() };
+    let history = standings_history(&Dataset::load(&path).unwrap(), &ranking_context, &[WINDOW_START + 2 * DAY, WINDOW_START + 3 * DAY], 30).unwrap();
+
+    let mut buffer = Vec::new();
+    history.write_csv(&mut buffer).unwrap();
+    let mut reader = csv::Reader::from_reader(&buffer[..]);
+    assert_eq!(reader.records().count(), 4);
+
+    let mut buffer = Vec::new();
+    history.write_json(&mut buffer).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+    assert_eq!(json["points"].as_array().unwrap().len(), 4);
+    assert_eq!(json["window_days"], 30);
+}
+
+#[test]
+fn huge_windows_and_steps_start_at_the_beginning_of_time() {
+    let dataset = round_robin_dataset();
+    let ranking_context = RankingContext { time_grace_period: 0, ..context() };
+
+    let history = standings_history(&dataset, &ranking_context, &[WINDOW_START + 10 * DAY], u32::MAX).unwrap();
+    assert!(history.skipped.is_empty() && !history.points.is_empty());
+    assert_eq!(cutoffs(WINDOW_START, WINDOW_END, u32::MAX, Some(Weekday::Monday)).len(), 1);
+}
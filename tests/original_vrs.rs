@@ -0,0 +1,91 @@
+mod common;
+
+use alternative_vrs::*;
+use common::*;
+use std::f64::consts::{LN_10, PI};
+
+fn original_vrs() -> RankingContext {
+    RankingContext { time_grace_period: 0, rating_system: RatingSystemKind::OriginalVrs, ..context() }
+}
+
+// The formula VRS published, before the constants were worked out
+#[test]
+fn elo_result_matches_the_published_formula() {
+    let q = LN_10 / 400.0;
+    let g = 1.0 / (1.0 + q * q * 16875.0 / (PI * PI)).sqrt();
+
+    for (winner, loser, info) in [(1500.0, 1500.0, 1.0), (1800.0, 1200.0, 0.5), (1000.0, 1900.0, 0.9)] {
+        let ev = 1.0 / (1.0 + 10f64.powf(g * (loser - winner) / 400.0));
+        let published = q * g * (1.0 - ev) * info / (1.0 / 5625.0 + q * q * g * g * ev * (1.0 - ev) * info * info);
+
+        assert!((vrs_elo_result(winner, loser, info) - published).abs() < 1e-9);
+        assert!((vrs_win_probability(winner, loser) - ev).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn seeds_come_from_the_vrs_factors() {
+    let ranking_context = original_vrs();
+    let (matches, events, mut teams) = round_robin_dataset().window(&ranking_context).unwrap();
+    seed_vrs_teams(&matches, &events, &mut teams, &ranking_context).unwrap();
+
+    let alpha = teams.iter().find(|t| t.name == "Alpha").unwrap();
+    let charlie = teams.iter().find(|t| t.name == "Charlie").unwrap();
+
+    // Alpha won the most LAN matches and the most prize money, so it's the reference for both
+    assert_eq!(alpha.event_participation, 1.0);
+    assert_eq!(alpha.prize_money, 1.0);
+    assert!(charlie.event_participation < 1.0 && charlie.prize_money < 1.0);
+
+    assert_eq!(alpha.elo, ranking_context.max_seeded_rank);
+    assert!(teams.iter().all(|t| t.elo == t.seed_points));
+}
+
+#[test]
+fn ratings_move_once_per_match() {
+    let ranking_context = original_vrs();
+    let (matches, events, mut teams) = round_robin_dataset().window(&ranking_context).unwrap();
+
+    let mut seeded = teams.clone();
+    seed_vrs_teams(&matches, &events, &mut seeded, &ranking_context).unwrap();
+
+    let system = rank(&matches, &events, &mut teams, &ranking_context).unwrap();
+    assert_eq!(system.kind(), RatingSystemKind::OriginalVrs);
+
+    // Replaying the matches by hand gives the same ratings
+    for m in &matches {
+        let change = vrs_elo_result(seeded[m.winner.0].elo, seeded[m.loser().0].elo, m.information_context);
+        seeded[m.winner.0].elo += change;
+        seeded[m.loser().0].elo -= change;
+    }
+    assert_eq!(teams.iter().map(|t| t.elo).collect::<Vec<_>>(), seeded.iter().map(|t| t.elo).collect::<Vec<_>>());
+
+    // And it's judged by its own odds
+    let alpha = teams.iter().find(|t| t.name == "Alpha").unwrap();
+    let bravo = teams.iter().find(|t| t.name == "Bravo").unwrap();
+    assert_eq!(system.predict(alpha, bravo, &ranking_context), vrs_win_probability(alpha.elo, bravo.elo));
+    assert!(analyze_fit(&teams, &matches, &ranking_context, false).is_ok());
+}
+
+#[test]
+fn bounty_collected_scales_by_the_listed_prize_pool() {
+    let listed = |prize_pool: &str| {
+        let mut feed = Feed::default().event(7, &[(1, 101, 1000.0, false), (2, 102, 500.0, false)]).prize_pool(prize_pool);
+        for day in 1..=6 {
+            feed = feed.match_(WINDOW_START + day * DAY, 7, ALPHA, BRAVO, 1).match_(WINDOW_START + day * DAY + 1, 7, BRAVO, CHARLIE, 1);
+        }
+        let ranking_context = original_vrs();
+        let (matches, events, mut teams) = Dataset::load(&feed.write()).unwrap().window(&ranking_context).unwrap();
+        seed_vrs_teams(&matches, &events, &mut teams, &ranking_context).unwrap();
+        (events[0].prize_pool, events[0].listed_prize_pool, teams)
+    };
+
+    let (summed, parsed, big) = listed("$1,000,000");
+    assert_eq!((summed, parsed), (1500.0, 1_000_000.0));
+
+    // Without a listed pool the wins are worth next to nothing, even though the prize distribution is the same
+    let (_, parsed, small) = listed("TBA");
+    assert_eq!(parsed, 0.0);
+    let alpha = |teams: &[Team]| teams.iter().find(|t| t.name == "Alpha").unwrap().opponent_winnings;
+    assert!(alpha(&big) > alpha(&small));
+}
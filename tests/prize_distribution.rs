@@ -0,0 +1,106 @@
+mod common;
+
+use alternative_vrs::*;
+use common::*;
+
+fn team_id(teams: &[Team], name: &str) -> TeamId {
+    teams.iter().find(|t| t.name == name).unwrap().id
+}
+
+// (team name, placement) for every placement that made it into the ranking
+fn counted_placements(event: &Event, teams: &[Team]) -> Vec<(String, u32)> {
+    let mut placements: Vec<(String, u32)> = event.prize_distribution.iter()
+        .filter_map(|pd| pd.team.map(|team| (teams[team.0].name.clone(), pd.placement)))
+        .collect();
+    placements.sort();
+    placements
+}
+
+#[test]
+fn both_team_slots_are_counted() {
+    let path = Feed::default()
+        .event(1, &[(1, ALPHA.0, 1000.0, false), (2, BRAVO.0, 500.0, false)])
+        .match_(WINDOW_START + DAY, 1, ALPHA, BRAVO, 1)
+        .write();
+
+    let ranking_context = context();
+    let (matches, events, mut teams) = load_data(path, &ranking_context).unwrap();
+
+    assert_eq!(counted_placements(&events[0], &teams), vec![("Alpha".to_string(), 1), ("Bravo".to_string(), 2)]);
+
+    gen_rank_new(&matches, &events, &mut teams, &ranking_context).unwrap();
+    assert!(teams[team_id(&teams, "Alpha").0].adjusted_winnings > 0.0);
+    assert!(teams[team_id(&teams, "Bravo").0].adjusted_winnings > 0.0);
+}
+
+#[test]
+fn shared_placements_are_all_counted() {
+    let path = Feed::default()
+        .event(1, &[(1, ALPHA.0, 1000.0, false), (2, BRAVO.0, 250.0, true), (2, CHARLIE.0, 250.0, true)])
+        .match_(WINDOW_START + DAY, 1, ALPHA, BRAVO, 1)
+        .match_(WINDOW_START + DAY + 1, 1, CHARLIE, ALPHA, 2)
+        .write();
+
+    let (_, events, teams) = load_data(path, &context()).unwrap();
+
+    assert_eq!(counted_placements(&events[0], &teams), vec![
+        ("Alpha".to_string(), 1),
+        ("Bravo".to_string(), 2),
+        ("Charlie".to_string(), 2),
+    ]);
+}
+
+#[test]
+fn core_indices_never_collide_with_hltv_ids() {
+    // HLTV IDs 0 and 1 are also valid core indices. Cores are found newest first, so Alpha (HLTV 1) becomes core 0
+    // and Bravo (HLTV 0) becomes core 1. Remapping a placement twice would swap them.
+    let alpha = (1, "Alpha", 1);
+    let bravo = (0, "Bravo", 11);
+
+    let path = Feed::default()
+        .event(1, &[(1, alpha.0, 1000.0, false), (2, bravo.0, 500.0, false)])
+        .match_(WINDOW_START + DAY, 1, bravo, alpha, 1)
+        .match_(WINDOW_START + 2 * DAY, 1, alpha, bravo, 1)
+        .match_(WINDOW_START + 3 * DAY, 1, alpha, bravo, 2)
+        .write();
+
+    let (_, events, teams) = load_data(path, &context()).unwrap();
+
+    assert_eq!(team_id(&teams, "Alpha"), TeamId(0));
+    assert_eq!(counted_placements(&events[0], &teams), vec![("Alpha".to_string(), 1), ("Bravo".to_string(), 2)]);
+}
+
+#[test]
+fn placements_go_to_the_core_that_played_the_event() {
+    // Alpha's HLTV ID fields a different core at the second event, which should get that event's prize
+    let new_alpha = (ALPHA.0, "Alpha", 31);
+
+    let path = Feed::default()
+        .event(1, &[(1, ALPHA.0, 1000.0, false)])
+        .event(2, &[(1, ALPHA.0, 2000.0, false)])
+        .match_(WINDOW_START + DAY, 1, ALPHA, BRAVO, 1)
+        .match_(WINDOW_START + 2 * DAY, 2, new_alpha, BRAVO, 1)
+        .write();
+
+    let (_, events, teams) = load_data(path, &context()).unwrap();
+
+    let old_core = teams.iter().find(|t| t.core[0].player_id == 1).unwrap().id;
+    let new_core = teams.iter().find(|t| t.core[0].player_id == 31).unwrap().id;
+
+    assert_eq!(events[0].prize_distribution[0].team, Some(old_core));
+    assert_eq!(events[1].prize_distribution[0].team, Some(new_core));
+}
+
+#[test]
+fn placements_without_matches_in_the_window_are_not_counted() {
+    let path = Feed::default()
+        .event(1, &[(1, ALPHA.0, 1000.0, false), (2, CHARLIE.0, 500.0, false)])
+        .event(2, &[])
+        .match_(WINDOW_START + DAY, 1, ALPHA, BRAVO, 1)
+        .match_(WINDOW_START + DAY, 2, CHARLIE, BRAVO, 1)
+        .write();
+
+    let (_, events, teams) = load_data(path, &context()).unwrap();
+
+    assert_eq!(counted_placements(&events[0], &teams), vec![("Alpha".to_string(), 1)]);
+}
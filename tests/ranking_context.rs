@@ -0,0 +1,73 @@
+mod common;
+
+use alternative_vrs::*;
+use common::*;
+
+#[test]
+fn toml_only_needs_the_fields_it_changes() {
+    let path = temp_file("toml", "elo_k = 24.0\ntop_outlier_count = 3\n\n[core_matching]\nrule = \"rolling\"\nmin_overlap = 4\n");
+    let ranking_context = RankingContext::from_file(&path).unwrap();
+
+    assert_eq!(ranking_context.elo_k, 24.0);
+    assert_eq!(ranking_context.top_outlier_count, 3);
+    assert_eq!(ranking_context.core_matching, CoreMatching::Rolling { min_overlap: 4 });
+
+    // Everything else keeps its default
+    let default = RankingContext::default();
+    assert_eq!(ranking_context.elo_delta, default.elo_delta);
+    assert_eq!(ranking_context.time_grace_period, default.time_grace_period);
+}
+
+#[test]
+fn json_only_needs_the_fields_it_changes() {
+    let path = temp_file("json", r#"{ "opponent_winnings_weight": 0.5, "min_matches_for_ranking": 3 }"#);
+    let ranking_context = RankingContext::from_file(&path).unwrap();
+
+    // The correctly spelled name is an alias
+    assert_eq!(ranking_context.opponet_winnings_weight, 0.5);
+    assert_eq!(ranking_context.min_matches_for_ranking, 3);
+    assert_eq!(ranking_context.prize_money_weight, RankingContext::default().prize_money_weight);
+}
+
+#[test]
+fn unreadable_and_unknown_configs_are_rejected() {
+    assert!(matches!(RankingContext::from_file("/nonexistent/model.toml"), Err(ContextError::Io(..))));
+    assert!(matches!(RankingContext::from_file(temp_file("toml", "elo_kay = 24.0\n")), Err(ContextError::Parse(..))));
+    assert!(matches!(RankingContext::from_file(temp_file("json", "{ \"elo_k\": ")), Err(ContextError::Parse(..))));
+}
+
+#[test]
+fn zero_top_outlier_count_is_rejected() {
+    let path = temp_file("toml", "top_outlier_count = 0\n");
+    assert!(matches!(RankingContext::from_file(&path), Err(ContextError::ZeroTopOutlierCount)));
+}
+
+#[test]
+fn the_window_has_to_be_longer_than_the_grace_period() {
+    let ranking_context = RankingContext { time_grace_period: 10 * DAY, ..context() };
+    assert!(ranking_context.validate().is_ok());
+
+    let too_short = RankingContext { time_window_end: WINDOW_START + 5 * DAY, ..ranking_context.clone() };
+    assert!(matches!(too_short.validate(), Err(ContextError::WindowShorterThanGracePeriod { .. })));
+
+    // Exactly as long would leave nothing for time_mod to divide by
+    let exactly = RankingContext { time_window_end: WINDOW_START + 10 * DAY, ..ranking_context.clone() };
+    assert!(matches!(exactly.validate(), Err(ContextError::WindowShorterThanGracePeriod { .. })));
+    let empty = RankingContext { time_window_end: WINDOW_START, time_grace_period: 0, ..ranking_context };
+    assert!(matches!(empty.validate(), Err(ContextError::WindowShorterThanGracePeriod { .. })));
+
+    let path = temp_file("json", &format!(r#"{{ "time_window_start": {WINDOW_START}, "time_window_end": {WINDOW_START} }}"#));
+    assert!(matches!(RankingContext::from_file(&path), Err(ContextError::WindowShorterThanGracePeriod { .. })));
+}
+
+#[test]
+fn negative_and_nan_weights_are_rejected() {
+    let path = temp_file("toml", "prize_money_weight = -1.0\n");
+    assert!(matches!(RankingContext::from_file(&path), Err(ContextError::NegativeWeight { field: "prize_money_weight", .. })));
+
+    let nan = RankingContext { event_participation_weight: f64::NAN, ..context() };
+    assert!(matches!(nan.validate(), Err(ContextError::NegativeWeight { field: "event_participation_weight", .. })));
+
+    // Zero turns a factor off, which is fine
+    assert!(RankingContext { opponent_network_weight: 0.0, ..context() }.validate().is_ok());
+}
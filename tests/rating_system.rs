@@ -0,0 +1,81 @@
+mod common;
+
+use alternative_vrs::*;
+use common::*;
+
+// Plain Elo on whole matches, starting everyone at 1000
+struct MatchElo {
+    updates: usize,
+}
+
+impl RatingSystem for MatchElo {
+    fn kind(&self) -> RatingSystemKind {
+        RatingSystemKind::AlternativeVrs
+    }
+
+    fn seed(&mut self, _matches: &[Match], _events: &[Event], teams: &mut [Team], _ranking_context: &RankingContext) -> alternative_vrs::Result<()> {
+        for t in teams.iter_mut() {
+            t.elo = 1000.0;
+        }
+        Ok(())
+    }
+
+    fn update_match(&mut self, m: &Match, teams: &mut [Team], ranking_context: &RankingContext) {
+        let change = elo_result(teams[m.winner.0].elo, teams[m.loser().0].elo, ranking_context);
+        teams[m.winner.0].elo += change;
+        teams[m.loser().0].elo -= change;
+        self.updates += 1;
+    }
+
+    fn predict(&self, team: &Team, opponent: &Team, ranking_context: &RankingContext) -> f64 {
+        win_probability(team.elo, opponent.elo, ranking_context)
+    }
+}
+
+#[test]
+fn the_default_system_is_gen_rank_new() {
+    let dataset = round_robin_dataset();
+    let ranking_context = RankingContext { time_grace_period: 0, ..context() };
+    assert_eq!(ranking_context.rating_system, RatingSystemKind::AlternativeVrs);
+
+    let (matches, events, mut expected) = dataset.window(&ranking_context).unwrap();
+    gen_rank_new(&matches, &events, &mut expected, &ranking_context).unwrap();
+
+    let (matches, events, mut teams) = dataset.window(&ranking_context).unwrap();
+    let system = rank(&matches, &events, &mut teams, &ranking_context).unwrap();
+    assert_eq!(system.kind(), RatingSystemKind::AlternativeVrs);
+    assert_eq!(teams.iter().map(|t| t.elo).collect::<Vec<_>>(), expected.iter().map(|t| t.elo).collect::<Vec<_>>());
+}
+
+#[test]
+fn a_system_can_rate_whole_matches() {
+    let dataset = round_robin_dataset();
+    let ranking_context = RankingContext { time_grace_period: 0, ..context() };
+    let (matches, events, mut teams) = dataset.window(&ranking_context).unwrap();
+
+    let mut system = MatchElo { updates: 0 };
+    run_rating_system(&mut system, &matches, &events, &mut teams, &ranking_context).unwrap();
+    assert_eq!(system.updates, matches.len());
+
+    // Rating points only move between teams
+    let total: f64 = teams.iter().map(|t| t.elo).sum();
+    assert!((total - 1000.0 * teams.len() as f64).abs() < 1e-9);
+
+    let alpha = teams.iter().find(|t| t.name == "Alpha").unwrap();
+    let charlie = teams.iter().find(|t| t.name == "Charlie").unwrap();
+    assert!(system.predict(alpha, charlie, &ranking_context) > 0.5);
+
+    let evaluation = evaluate(&teams, &matches, &ranking_context, &system, 10).unwrap();
+    assert_eq!(evaluation.matches.predictions, matches.len());
+}
+
+#[test]
+fn the_system_is_read_from_the_config() {
+    let ranking_context: RankingContext = serde_json::from_value(serde_json::json!({
+        "rating_system": { "system": "alternative_vrs" },
+    })).unwrap();
+    assert_eq!(ranking_context.rating_system, RatingSystemKind::AlternativeVrs);
+    assert_eq!(RatingSystemKind::AlternativeVrs.to_string(), "Alternative VRS");
+
+    assert!(serde_json::from_value::<RankingContext>(serde_json::json!({ "rating_system": { "system": "chess" } })).is_err());
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use alternative_vrs::*;
+use common::*;
+
+fn lineup_from(countries: &[&str]) -> Vec<Player> {
+    countries.iter().enumerate().map(|(idx, iso)| Player { country_iso: iso.to_string(), ..player(idx as u16) }).collect()
+}
+
+#[test]
+fn countries_map_to_valve_regions() {
+    assert_eq!(Region::from_country_iso("DK"), Some(Region::Europe));
+    assert_eq!(Region::from_country_iso("kz"), Some(Region::Europe));
+    assert_eq!(Region::from_country_iso("BR"), Some(Region::Americas));
+    assert_eq!(Region::from_country_iso("AU"), Some(Region::Asia));
+    assert_eq!(Region::from_country_iso("??"), None);
+}
+
+#[test]
+fn majority_needs_more_than_half() {
+    let mixed = lineup_from(&["DK", "SE", "NO", "BR", "AU"]);
+    assert_eq!(assign_region(&mixed, RegionRule::Majority), Some(Region::Europe));
+
+    let split = lineup_from(&["DK", "SE", "BR", "US", "AU"]);
+    assert_eq!(assign_region(&split, RegionRule::Majority), None);
+
+    // Unknown countries still count towards the lineup
+    let unknown = lineup_from(&["DK", "SE", "??", "??", "??"]);
+    assert_eq!(assign_region(&unknown, RegionRule::Majority), None);
+    assert_eq!(assign_region(&unknown, RegionRule::Plurality), Some(Region::Europe));
+}
+
+#[test]
+fn plurality_and_at_least_refuse_ties() {
+    let split = lineup_from(&["DK", "SE", "BR", "US", "AU"]);
+    assert_eq!(assign_region(&split, RegionRule::Plurality), None);
+    assert_eq!(assign_region(&split, RegionRule::AtLeast { players: 2 }), None);
+
+    let leaning = lineup_from(&["DK", "SE", "BR", "CN", "AU"]);
+    assert_eq!(assign_region(&leaning, RegionRule::Plurality), None);
+
+    let american = lineup_from(&["BR", "BR", "BR", "DK", "DK"]);
+    assert_eq!(assign_region(&american, RegionRule::AtLeast { players: 3 }), Some(Region::Americas));
+    assert_eq!(assign_region(&american, RegionRule::AtLeast { players: 4 }), None);
+}
+
+#[test]
+fn load_data_assigns_regions_from_the_core() {
+    let path = Feed::default()
+        .event(7, &[])
+        .match_(WINDOW_START + DAY, 7, (101, "Alpha", 1), (102, "Bravo", 11), 1)
+        .write();
+
+    // The common feed lists every player as Norwegian
+    let (_, _, teams) = load_data(path.clone(), &context()).unwrap();
+    assert!(teams.iter().all(|t| t.region == Some(Region::Europe)));
+
+    let ranking_context = RankingContext { region_rule: RegionRule::AtLeast { players: 0 }, ..context() };
+    assert!(load_data(path, &ranking_context).is_err());
+
+    // More players than a core has would leave every team without a region
+    let ranking_context = RankingContext { region_rule: RegionRule::AtLeast { players: 6 }, ..context() };
+    assert!(matches!(ranking_context.validate(), Err(ContextError::InvalidRegionRule(_))));
+    assert!(RankingContext { region_rule: RegionRule::AtLeast { players: 5 }, ..context() }.validate().is_ok());
+}
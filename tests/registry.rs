@@ -0,0 +1,135 @@
+mod common;
+
+use alternative_vrs::*;
+use common::*;
+
+fn feed() -> String {
+    Feed::default()
+        .event(7, &[(1, ALPHA.0, 1000.0, false), (2, BRAVO.0, 500.0, false)])
+        .match_(WINDOW_START + DAY, 7, ALPHA, BRAVO, 1)
+        .match_(WINDOW_START + 2 * DAY, 7, BRAVO, CHARLIE, 1)
+        .match_(WINDOW_START + 3 * DAY, 7, CHARLIE, ALPHA, 2)
+        .write()
+}
+
+#[test]
+fn hltv_ids_are_kept_next_to_our_own() {
+    let (matches, events, teams) = load_data(feed(), &context()).unwrap();
+    let registry = Registry::new(&events, &teams);
+
+    for m in &matches {
+        assert_eq!(registry.teams(m.team_1_id), &[m.team_1]);
+        assert_eq!(registry.teams(m.team_2_id), &[m.team_2]);
+        assert_eq!(m.event_id, 7);
+        assert_eq!(registry.event(m.event_id), Some(m.event));
+    }
+
+    let alpha = teams.iter().find(|t| t.name == "Alpha").unwrap();
+    assert_eq!(registry.hltv_team_ids(alpha.id), &[ALPHA.0]);
+    assert_eq!(registry.hltv_event_id(EventId(0)), Some(7));
+    assert_eq!(events[0].prize_distribution[0].team_id, ALPHA.0);
+}
+
+#[test]
+fn ranking_survives_sorted_teams() {
+    let ranking_context = context();
+
+    let (matches, events, mut teams) = load_data(feed(), &ranking_context).unwrap();
+    gen_rank_new(&matches, &events, &mut teams, &ranking_context).unwrap();
+
+    let (_, _, mut shuffled) = load_data(feed(), &ranking_context).unwrap();
+    shuffled.reverse();
+    gen_rank_new(&matches, &events, &mut shuffled, &ranking_context).unwrap();
+
+    for t in &teams {
+        let same = shuffled.iter().find(|s| s.id == t.id).unwrap();
+        assert_eq!(t.elo, same.elo);
+    }
+
+    // The list keeps the order it was handed in
+    let order: Vec<TeamId> = shuffled.iter().map(|t| t.id).collect();
+    assert_eq!(order, vec![TeamId(2), TeamId(1), TeamId(0)]);
+
+    // Sorting by Elo for display and ranking again doesn't undo the sort
+    shuffled.sort_by(|a, b| b.elo.total_cmp(&a.elo));
+    let sorted: Vec<TeamId> = shuffled.iter().map(|t| t.id).collect();
+    rank(&matches, &events, &mut shuffled, &ranking_context).unwrap();
+    assert_eq!(shuffled.iter().map(|t| t.id).collect::<Vec<_>>(), sorted);
+
+    // The fit only reads the teams, so it copes with any order
+    teams.sort_by(|a, b| b.elo.total_cmp(&a.elo));
+    assert_eq!(
+        analyze_fit(&teams, &matches, &ranking_context, false).unwrap(),
+        analyze_fit(&shuffled, &matches, &ranking_context, false).unwrap(),
+    );
+}
+
+#[test]
+fn ranking_refuses_filtered_teams() {
+    let ranking_context = context();
+
+    let (matches, events, mut teams) = load_data(feed(), &ranking_context).unwrap();
+    teams.retain(|t| t.name != "Bravo");
+
+    assert!(matches!(gen_rank_new(&matches, &events, &mut teams, &ranking_context), Err(Error::MissingTeam(_))));
+}
+
+#[test]
+fn map_adjustments_check_the_teams_too() {
+    let ranking_context = context();
+    let (matches, _, teams) = load_data(feed(), &ranking_context).unwrap();
+
+    let mut in_order = teams.clone();
+    map_elo_adjustments(&matches, &mut in_order, &ranking_context).unwrap();
+
+    // Each team gets its own Elo, not the one at its position
+    let mut reversed = teams.clone();
+    reversed.reverse();
+    map_elo_adjustments(&matches, &mut reversed, &ranking_context).unwrap();
+    for t in &reversed {
+        assert_eq!(t.elo, in_order[t.id.0].elo);
+    }
+
+    let mut short = teams.clone();
+    short.pop();
+    assert!(matches!(map_elo_adjustments(&matches, &mut short, &ranking_context), Err(Error::MissingTeam(_))));
+    assert!(matches!(map_elo_adjustments_with_ledger(&matches, &mut short, &ranking_context), Err(Error::MissingTeam(_))));
+}
+
+#[test]
+fn scoring_survives_filtered_teams() {
+    let ranking_context = context();
+    let (matches, events, mut teams) = load_data(feed(), &ranking_context).unwrap();
+    let system = rank(&matches, &events, &mut teams, &ranking_context).unwrap();
+
+    // Without Bravo, only Charlie against Alpha is left
+    let bravo = teams.iter().find(|t| t.name == "Bravo").unwrap().id;
+    let filtered: Vec<Team> = teams.iter().filter(|t| t.id != bravo).cloned().collect();
+    let left: Vec<Match> = matches.iter().filter(|m| !m.is_in_game(bravo)).cloned().collect();
+    assert_eq!(left.len(), 1);
+
+    assert_eq!(
+        analyze_fit(&filtered, &matches, &ranking_context, false).unwrap(),
+        analyze_fit(&teams, &left, &ranking_context, false).unwrap(),
+    );
+
+    let predictions = match_predictions(&filtered, &matches, &ranking_context, system.as_ref()).unwrap();
+    assert_eq!(predictions, match_predictions(&teams, &left, &ranking_context, system.as_ref()).unwrap());
+    assert_eq!(predictions.len(), 1);
+}
+
+#[test]
+fn team_fit_leaves_out_teams_with_nobody_left_to_play() {
+    let ranking_context = context();
+    let (matches, events, mut teams) = load_data(feed(), &ranking_context).unwrap();
+    rank(&matches, &events, &mut teams, &ranking_context).unwrap();
+
+    // On its own, every opponent Alpha played is missing
+    let alpha: Vec<Team> = teams.iter().filter(|t| t.name == "Alpha").cloned().collect();
+    assert!(team_fit(&alpha, &matches, &ranking_context, false).unwrap().is_empty());
+
+    let pair: Vec<Team> = teams.iter().filter(|t| t.name != "Charlie").cloned().collect();
+    let fit = team_fit(&pair, &matches, &ranking_context, false).unwrap();
+    assert_eq!(fit.len(), 2);
+    assert!(fit.iter().all(|(abs_diff, net_diff)| abs_diff.is_finite() && net_diff.is_finite()));
+}
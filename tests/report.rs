@@ -0,0 +1,65 @@
+mod common;
+
+use alternative_vrs::*;
+use common::*;
+
+fn teams() -> Vec<Team> {
+    vec![
+        team(0, "Alpha", 1, 1500.0),
+        team(1, "Bravo", 11, 1700.04),
+        team(2, "Charlie", 21, 1699.96),
+        team(3, "Delta", 31, 1800.0),
+        team(4, "Echo", 41, 1400.0),
+    ]
+}
+
+#[test]
+fn teams_are_ranked_by_elo_with_shared_ranks_for_ties() {
+    let teams = teams();
+    let ranked: Vec<(usize, &str)> = ranked_teams(&teams, &context()).into_iter().map(|(rank, t)| (rank, t.name.as_str())).collect();
+
+    // Bravo and Charlie both show up as 1700.0
+    assert_eq!(ranked, vec![(1, "Delta"), (2, "Bravo"), (2, "Charlie"), (4, "Alpha"), (5, "Echo")]);
+}
+
+#[test]
+fn ineligible_teams_are_left_out() {
+    let mut teams = teams();
+    teams.iter_mut().for_each(|t| t.matches_played = 5);
+    teams[3].matches_played = 0;
+
+    let ranking_context = RankingContext { min_matches_for_ranking: 1, ..context() };
+    let ranked = ranked_teams(&teams, &ranking_context);
+
+    assert_eq!(ranked.len(), 4);
+    assert_eq!((ranked[0].0, ranked[0].1.name.as_str()), (1, "Bravo"));
+}
+
+#[test]
+fn markdown_report_has_the_window_settings_and_a_row_per_team() {
+    let mut teams = teams();
+    teams[0].name = "Alpha | Academy".to_string();
+    teams[0].seed_points = 1400.0;
+
+    let report = markdown_report(&teams, &context());
+
+    assert!(report.contains("Matches from 2023-03-02 to 2023-08-29."));
+    assert!(report.contains("- Elo K: 32\n"));
+    assert!(report.contains("| Rank | Team | Elo | Seed | Diff |"));
+
+    let rows: Vec<&str> = report.lines().filter(|l| l.starts_with("| ") && !l.starts_with("| Rank") && !l.starts_with("| ---")).collect();
+    assert_eq!(rows.len(), 5);
+    assert!(rows[0].starts_with("| 1 | Delta | 1800.0 |"));
+    assert!(rows[3].starts_with("| 4 | Alpha \\| Academy | 1500.0 | 1400.0 | +100.0 |"));
+}
+
+#[test]
+fn html_report_escapes_team_names() {
+    let mut teams = teams();
+    teams[4].name = "<Echo & Co>".to_string();
+
+    let report = html_report(&teams, &context());
+
+    assert!(report.contains("<td>&lt;Echo &amp; Co&gt;</td>"));
+    assert_eq!(report.matches("<tr>").count(), 6);
+}
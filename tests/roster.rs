@@ -0,0 +1,80 @@
+mod common;
+
+use alternative_vrs::*;
+use alternative_vrs::util::{format_date, parse_date};
+use common::*;
+
+// Bravo's players are kept clear of every lineup Alpha plays with
+const BRAVO: (usize, &str, u16) = (102, "Bravo", 51);
+
+// Alpha plays twice with 1-5, once with a stand-in (2-6), once more with 1-5 and then signs 3-7 for good
+fn feed() -> String {
+    Feed::default()
+        .event(7, &[])
+        .match_(WINDOW_START + DAY, 7, ALPHA, BRAVO, 1)
+        .match_(WINDOW_START + 2 * DAY, 7, ALPHA, BRAVO, 1)
+        .match_(WINDOW_START + 3 * DAY, 7, (101, "Alpha", 2), BRAVO, 1)
+        .match_(WINDOW_START + 4 * DAY, 7, ALPHA, BRAVO, 1)
+        .match_(WINDOW_START + 5 * DAY, 7, (101, "Alpha", 3), BRAVO, 1)
+        .match_(WINDOW_START + 6 * DAY, 7, (101, "Alpha", 3), BRAVO, 1)
+        .write()
+}
+
+fn alpha() -> Team {
+    let (_, _, teams) = load_data(feed(), &context()).unwrap();
+    teams.into_iter().find(|t| t.name == "Alpha").unwrap()
+}
+
+fn ids(players: &[Player]) -> Vec<u16> {
+    players.iter().map(|p| p.player_id).collect()
+}
+
+#[test]
+fn lineups_are_dated_and_merged_while_unchanged() {
+    let alpha = alpha();
+    let lineups = alpha.roster.lineups();
+
+    assert_eq!(lineups.len(), 4);
+    assert_eq!(ids(&lineups[0].players), vec![1, 2, 3, 4, 5]);
+    assert_eq!((lineups[0].first_match, lineups[0].last_match), (WINDOW_START + DAY, WINDOW_START + 2 * DAY));
+    assert_eq!(lineups[0].matches_played, 2);
+    assert_eq!(ids(&lineups[1].players), vec![2, 3, 4, 5, 6]);
+    assert_eq!(lineups[3].matches_played, 2);
+
+    let bravo_lineups: Vec<_> = load_data(feed(), &context()).unwrap().2.into_iter()
+        .find(|t| t.name == "Bravo").unwrap()
+        .roster.lineups().to_vec();
+    assert_eq!(bravo_lineups.len(), 1);
+    assert_eq!(bravo_lineups[0].matches_played, 6);
+}
+
+#[test]
+fn current_lineup_is_the_latest_not_the_core() {
+    let alpha = alpha();
+
+    assert_eq!(ids(&alpha.roster.current().unwrap().players), vec![3, 4, 5, 6, 7]);
+    assert_eq!(ids(&alpha.roster.lineup_at(WINDOW_START + 3 * DAY).unwrap().players), vec![2, 3, 4, 5, 6]);
+    assert_eq!(alpha.roster.lineup_at(WINDOW_START), None);
+}
+
+#[test]
+fn changes_include_stand_ins() {
+    let changes = alpha().roster.changes();
+
+    let summary: Vec<(u32, Vec<u16>, Vec<u16>)> = changes.iter().map(|c| (c.time, ids(&c.joined), ids(&c.left))).collect();
+    assert_eq!(summary, vec![
+        (WINDOW_START + 3 * DAY, vec![6], vec![1]),
+        (WINDOW_START + 4 * DAY, vec![1], vec![6]),
+        (WINDOW_START + 5 * DAY, vec![6, 7], vec![1, 2]),
+    ]);
+}
+
+#[test]
+fn format_date_is_the_inverse_of_parse_date() {
+    assert_eq!(format_date(WINDOW_END), "2023-08-29");
+    assert_eq!(format_date(0), "1970-01-01");
+
+    for date in ["2000-02-29", "2023-12-31", "2024-03-01"] {
+        assert_eq!(format_date(parse_date(date).unwrap()), date);
+    }
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use alternative_vrs::*;
+use common::*;
+
+fn range(s: &str) -> ParameterRange {
+    s.parse().unwrap()
+}
+
+#[test]
+fn ranges_parse_and_include_both_ends() {
+    assert_eq!(range("elo_k=16:64:16"), ParameterRange { field: "elo_k".to_string(), from: 16.0, to: 64.0, step: 16.0 });
+    assert_eq!(range("elo_k=16:64:16").grid_values(), vec![16.0, 32.0, 48.0, 64.0]);
+    assert_eq!(range("prize_money_weight=0:1:0.1").grid_values().len(), 11);
+    assert_eq!(range("elo_delta=400").grid_values(), vec![400.0]);
+
+    assert!("elo_k".parse::<ParameterRange>().is_err());
+    assert!("elo_k=64:16".parse::<ParameterRange>().is_err());
+    assert!("elo_k=a:b".parse::<ParameterRange>().is_err());
+}
+
+#[test]
+fn set_field_by_name() {
+    let mut ranking_context = context();
+    ranking_context.set_field("elo_k", 20.5).unwrap();
+    ranking_context.set_field("opponent_winnings_weight", 0.5).unwrap();
+    ranking_context.set_field("factor_bucket_size", 7.6).unwrap();
+    assert_eq!((ranking_context.elo_k, ranking_context.opponet_winnings_weight, ranking_context.factor_bucket_size), (20.5, 0.5, 8));
+
+    assert!(matches!(ranking_context.set_field("elo", 1.0), Err(ContextError::NotANumericField(_))));
+    assert!(matches!(ranking_context.set_field("core_matching", 1.0), Err(ContextError::NotANumericField(_))));
+    assert!(matches!(ranking_context.set_field("top_outlier_count", -3.0), Err(ContextError::InvalidFieldValue { .. })));
+}
+
+#[test]
+fn combinations_cover_the_grid_and_random_draws_repeat() {
+    let grid = Sweep::grid(vec![range("elo_k=16:48:16"), range("elo_delta=200:400:200")]);
+    assert_eq!(grid.combinations(), vec![
+        vec![16.0, 200.0], vec![16.0, 400.0],
+        vec![32.0, 200.0], vec![32.0, 400.0],
+        vec![48.0, 200.0], vec![48.0, 400.0],
+    ]);
+
+    let random = |seed| Sweep::random(vec![range("elo_k=16:48"), range("elo_delta=200:400")], 20, seed).combinations();
+    let draws = random(3);
+    assert_eq!(draws.len(), 20);
+    assert_eq!(draws, random(3));
+    assert_ne!(draws, random(4));
+    assert!(draws.iter().all(|c| (16.0..=48.0).contains(&c[0]) && (200.0..=400.0).contains(&c[1])));
+}
+
+#[test]
+fn results_are_sorted_and_do_not_depend_on_threads() {
+    let dataset = round_robin_dataset();
+    let ranking_context = RankingContext { time_grace_period: 0, ..context() };
+
+    let mut sweep = Sweep::grid(vec![range("elo_k=8:64:8"), range("event_participation_weight=0:2:1")]);
+    sweep.threads = 1;
+    let single = sweep.run(&dataset, &ranking_context).unwrap();
+    sweep.threads = 4;
+    let several = sweep.run(&dataset, &ranking_context).unwrap();
+
+    assert_eq!(single.results.len(), 24);
+    assert!(single.results.windows(2).all(|pair| pair[0].error <= pair[1].error));
+
+    let rows = |results: &SweepResults| results.results.iter().map(|r| (r.values.clone(), r.error)).collect::<Vec<_>>();
+    assert_eq!(rows(&single), rows(&several));
+
+    // The sweep agrees with ranking the best combination by hand
+    let best = single.best().unwrap();
+    let mut by_hand = ranking_context.clone();
+    by_hand.elo_k = best.values[0];
+    by_hand.event_participation_weight = best.values[1];
+    let (matches, events, mut teams) = dataset.window(&by_hand).unwrap();
+    gen_rank_new(&matches, &events, &mut teams, &by_hand).unwrap();
+    assert_eq!(analyze_fit(&teams, &matches, &by_hand, false).unwrap(), best.error);
+}
+
+#[test]
+fn bad_combinations_fail_on_their_own() {
+    let dataset = round_robin_dataset();
+    let ranking_context = RankingContext { time_grace_period: 0, ..context() };
+
+    let results = Sweep::grid(vec![range("prize_money_weight=-1:1:1")]).run(&dataset, &ranking_context).unwrap();
+    assert_eq!(results.results.len(), 2);
+    assert_eq!(results.failed.len(), 1);
+    assert_eq!(results.failed[0].values, vec![-1.0]);
+
+    let mut buffer = Vec::new();
+    results.write_csv(&mut buffer).unwrap();
+    let mut reader = csv::Reader::from_reader(&buffer[..]);
+    assert_eq!(reader.headers().unwrap(), vec!["rank", "prize_money_weight", "error", "failure"]);
+    assert_eq!(reader.records().count(), 3);
+
+    assert!(Sweep::grid(vec![range("not_a_field=0:1")]).run(&dataset, &ranking_context).is_err());
+}
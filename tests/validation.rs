@@ -0,0 +1,155 @@
+mod common;
+
+use alternative_vrs::*;
+use alternative_vrs::validation::{Issue, Severity, ValidationIssue};
+use common::*;
+use std::process::Command;
+
+// Event 7 with a clean match first, so every other match is the second one in the file
+fn feed() -> Feed {
+    Feed::default().event(7, &[(1, ALPHA.0, 1000.0, false)]).match_(WINDOW_START + DAY, 7, ALPHA, BRAVO, 1)
+}
+
+fn validate(feed: Feed) -> ValidationReport {
+    Dataset::load(&feed.write()).unwrap().validate(&context())
+}
+
+// The only issue with the second match
+fn second_match(report: &ValidationReport) -> &ValidationIssue {
+    let issues: Vec<&ValidationIssue> = report.issues.iter().filter(|i| i.record.to_string().starts_with("match #1 ")).collect();
+    assert_eq!(issues.len(), 1, "{report}");
+    issues[0]
+}
+
+#[test]
+fn a_clean_feed_has_no_issues() {
+    let report = validate(feed().match_(WINDOW_START + 2 * DAY, 7, BRAVO, CHARLIE, 2));
+    assert!(report.issues.is_empty(), "{report}");
+    assert_eq!((report.matches_read, report.matches_kept, report.events_read), (2, 2, 1));
+}
+
+#[test]
+fn tied_maps_are_kept_with_a_warning() {
+    let report = validate(feed().match_with_maps(WINDOW_START + 2 * DAY, 7, ALPHA, BRAVO, 1, &[(13, 5), (15, 15), (16, 14)]));
+
+    let issue = second_match(&report);
+    assert_eq!(issue.issue, Issue::TiedMap { map_name: "de_anubis".to_string(), team_1_score: 15, team_2_score: 15 });
+    assert_eq!(issue.severity, Severity::Warning);
+    assert_eq!(report.matches_kept, 2);
+}
+
+#[test]
+fn a_winner_outside_one_and_two_is_dropped() {
+    let report = validate(feed().match_with_maps(WINDOW_START + 2 * DAY, 7, ALPHA, BRAVO, 3, &[(13, 5)]));
+
+    let issue = second_match(&report);
+    assert_eq!(issue.issue, Issue::InvalidWinningTeam { winning_team: 3 });
+    assert_eq!(issue.severity, Severity::Dropped);
+    assert_eq!(report.matches_kept, 1);
+    assert!(report.dropped_matches().contains(&1));
+}
+
+#[test]
+fn a_winner_that_lost_the_maps_is_kept_with_a_warning() {
+    let report = validate(feed().match_with_maps(WINDOW_START + 2 * DAY, 7, ALPHA, BRAVO, 1, &[(13, 5), (5, 13), (7, 13)]));
+
+    let issue = second_match(&report);
+    assert_eq!(issue.issue, Issue::WinnerDisagreesWithMaps { winning_team: 1, team_1_maps: 1, team_2_maps: 2 });
+    assert_eq!(issue.severity, Severity::Warning);
+}
+
+#[test]
+fn a_match_without_maps_is_kept_with_a_warning() {
+    let report = validate(feed().match_with_maps(WINDOW_START + 2 * DAY, 7, ALPHA, BRAVO, 2, &[]));
+
+    let issue = second_match(&report);
+    assert_eq!(issue.issue, Issue::NoMaps);
+    assert_eq!(issue.severity, Severity::Warning);
+    assert_eq!(report.matches_kept, 2);
+}
+
+#[test]
+fn duplicate_matches_are_dropped_in_either_order() {
+    let report = validate(feed().match_(WINDOW_START + DAY, 7, BRAVO, ALPHA, 2));
+
+    let issue = second_match(&report);
+    assert_eq!(issue.issue, Issue::DuplicateMatch { first_index: 0 });
+    assert_eq!(issue.severity, Severity::Dropped);
+    assert_eq!(report.matches_kept, 1);
+}
+
+#[test]
+fn a_missing_event_is_an_error_inside_the_window() {
+    let report = validate(feed().match_(WINDOW_START + 2 * DAY, 9, ALPHA, BRAVO, 1));
+
+    let issue = second_match(&report);
+    assert_eq!(issue.issue, Issue::UnknownEvent);
+    assert_eq!(issue.severity, Severity::Error);
+    assert!(matches!(report.first_error(), Some(Error::UnknownEvent { event_id: 9, .. })));
+}
+
+#[test]
+fn prize_money_for_an_unknown_team_is_only_a_warning() {
+    let report = validate(Feed::default().event(7, &[(1, 999, 1000.0, false)]).match_(WINDOW_START + DAY, 7, ALPHA, BRAVO, 1));
+
+    assert_eq!(report.issues.len(), 1);
+    assert_eq!(report.issues[0].issue, Issue::UnknownPrizeTeam { team_id: 999, placement: 1 });
+    assert_eq!(report.issues[0].severity, Severity::Warning);
+    assert!(report.first_error().is_none());
+}
+
+#[test]
+fn bad_records_outside_the_window_are_only_dropped() {
+    let path = feed()
+        .event(8, &[(1, 999, 500.0, false)])
+        .match_(WINDOW_START - DAY, 8, ALPHA, BRAVO, 1)
+        .match_(WINDOW_START - DAY, 9, ALPHA, CHARLIE, 1)
+        .write();
+    let dataset = Dataset::load(&path).unwrap();
+
+    let report = dataset.validate(&context());
+    assert_eq!(report.errors().count(), 0, "{report}");
+    assert!(report.issues.iter().any(|i| i.issue == Issue::UnknownEvent && i.severity == Severity::Dropped));
+    assert!(report.issues.iter().any(|i| matches!(i.issue, Issue::UnknownPrizeTeam { team_id: 999, .. }) && i.severity == Severity::Warning));
+
+    let (matches, _, _) = dataset.window(&context()).unwrap();
+    assert_eq!(matches.len(), 1);
+}
+
+#[test]
+fn json_lists_every_issue_with_its_record() {
+    let report = validate(feed().match_with_maps(WINDOW_START + 2 * DAY, 7, ALPHA, BRAVO, 3, &[(15, 15)]));
+    let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+
+    assert_eq!(json["matches_read"], 2);
+    assert_eq!(json["matches_kept"], 1);
+
+    let issues = json["issues"].as_array().unwrap();
+    let kinds: Vec<&str> = issues.iter().map(|i| i["issue"]["kind"].as_str().unwrap()).collect();
+    assert_eq!(kinds, vec!["invalid_winning_team", "tied_map"]);
+
+    assert_eq!(issues[0]["severity"], "dropped");
+    assert_eq!(issues[0]["record"], "match");
+    assert_eq!(issues[0]["index"], 1);
+    assert_eq!(issues[0]["team_1_name"], "Alpha");
+    assert_eq!(issues[0]["issue"]["winning_team"], 3);
+    assert_eq!(issues[1]["severity"], "warning");
+}
+
+#[test]
+fn the_validate_command_uses_the_config() {
+    let path = feed().match_(WINDOW_START + 20 * DAY, 7, ALPHA, BRAVO, 2).write();
+    let config = temp_file("toml", &format!(
+        "time_window_start = {WINDOW_START}\ntime_window_end = {}\ntime_grace_period = 0\n", WINDOW_START + 10 * DAY
+    ));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_alternative_vrs"))
+        .args(["validate", "--data", &path, "--config", &config, "--format", "json"])
+        .output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["matches_kept"], 1);
+    assert_eq!(json["issues"][0]["issue"]["kind"], "outside_window");
+    assert_eq!(json["issues"][0]["issue"]["window_end"], WINDOW_START + 10 * DAY);
+}